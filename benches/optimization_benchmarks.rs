@@ -6,7 +6,7 @@ use neurassembly::model::{
 
 fn optimize_benchmark(c: &mut Criterion) {
 	let config = OptimizationConfig::default();
-	let model = OptimizationModel::new(config);
+	let mut model = OptimizationModel::new(config).expect("failed to create optimization model");
 	let mut encoder = AssemblyEncoder::new();
 
 	// Sample assembly code for benchmarking
@@ -0,0 +1,4 @@
+pub mod routes;
+
+// Re-export main types
+pub use routes::setup_router;
@@ -19,22 +19,69 @@ pub struct OptimizeResponse {
 	optimized_assembly: String,
 }
 
+#[derive(Deserialize)]
+pub struct BatchOptimizeRequest {
+	items: Vec<OptimizeRequest>,
+}
+
+#[derive(Serialize)]
+pub struct BatchOptimizeResponse {
+	items: Vec<OptimizeResponse>,
+}
+
 async fn optimize_assembly(
 	Json(request): Json<OptimizeRequest>,
 ) -> Json<OptimizeResponse> {
 	let config = OptimizationConfig::default();
-	let model = OptimizationModel::new(config);
+	let mut model = match OptimizationModel::new(config) {
+		Ok(model) => model,
+		Err(e) => {
+			tracing::error!("failed to initialize optimization model: {}", e);
+			return Json(OptimizeResponse {
+				optimized_assembly: request.assembly,
+			});
+		}
+	};
 	let mut encoder = AssemblyEncoder::new();
 
 	let input_tokens = encoder.encode(&request.assembly);
-	let _optimized_tokens = model.optimize(&input_tokens);
+	let optimized_tokens = model.optimize(&input_tokens);
 
-	// For now, return the input as we haven't implemented the full optimization
 	Json(OptimizeResponse {
-		optimized_assembly: request.assembly,
+		optimized_assembly: encoder.decode(&optimized_tokens),
 	})
 }
 
+/// Same model-and-encoder setup as [`optimize_assembly`], built once and
+/// reused across the whole batch instead of once per item.
+async fn optimize_batch(
+	Json(request): Json<BatchOptimizeRequest>,
+) -> Json<BatchOptimizeResponse> {
+	let config = OptimizationConfig::default();
+	let mut model = match OptimizationModel::new(config) {
+		Ok(model) => model,
+		Err(e) => {
+			tracing::error!("failed to initialize optimization model: {}", e);
+			return Json(BatchOptimizeResponse {
+				items: request.items.into_iter().map(|item| OptimizeResponse {
+					optimized_assembly: item.assembly,
+				}).collect(),
+			});
+		}
+	};
+	let mut encoder = AssemblyEncoder::new();
+
+	let items = request.items.into_iter().map(|item| {
+		let input_tokens = encoder.encode(&item.assembly);
+		let optimized_tokens = model.optimize(&input_tokens);
+		OptimizeResponse {
+			optimized_assembly: encoder.decode(&optimized_tokens),
+		}
+	}).collect();
+
+	Json(BatchOptimizeResponse { items })
+}
+
 async fn health_check() -> &'static str {
 	"OK"
 }
@@ -42,5 +89,6 @@ async fn health_check() -> &'static str {
 pub fn setup_router() -> Router {
 	Router::new()
 		.route("/optimize", post(optimize_assembly))
+		.route("/optimize/batch", post(optimize_batch))
 		.route("/health", get(health_check))
 }
\ No newline at end of file
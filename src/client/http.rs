@@ -0,0 +1,182 @@
+use super::{AsyncClient, ClientResult, SyncClient};
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+#[derive(Serialize)]
+struct OptimizeRequestBody {
+    assembly: String,
+}
+
+#[derive(Deserialize)]
+struct OptimizeResponseBody {
+    optimized_assembly: String,
+}
+
+#[derive(Serialize)]
+struct BatchOptimizeRequestBody {
+    items: Vec<OptimizeRequestBody>,
+}
+
+#[derive(Deserialize)]
+struct BatchOptimizeResponseBody {
+    items: Vec<OptimizeResponseBody>,
+}
+
+/// Backoff policy for retrying transient failures against a Neurassembly
+/// server. Backoff doubles after each attempt.
+#[derive(Debug, Clone)]
+pub struct RetryConfig {
+    pub max_attempts: u32,
+    pub initial_backoff: Duration,
+}
+
+impl Default for RetryConfig {
+    fn default() -> Self {
+        Self { max_attempts: 3, initial_backoff: Duration::from_millis(100) }
+    }
+}
+
+/// HTTP-backed [`SyncClient`]/[`AsyncClient`] implementation, backed by one
+/// `reqwest` client per calling style: a blocking one and an async one.
+///
+/// The blocking client is built lazily on first use rather than in `new`,
+/// since `reqwest::blocking::Client::new` panics if constructed from inside
+/// a tokio runtime and `HttpClient` is commonly constructed from async code
+/// that only ever calls the [`AsyncClient`] half.
+pub struct HttpClient {
+    base_url: String,
+    blocking: std::sync::OnceLock<reqwest::blocking::Client>,
+    async_client: reqwest::Client,
+    retry: RetryConfig,
+}
+
+impl HttpClient {
+    pub fn new(base_url: impl Into<String>) -> Self {
+        Self::with_retry_config(base_url, RetryConfig::default())
+    }
+
+    pub fn with_retry_config(base_url: impl Into<String>, retry: RetryConfig) -> Self {
+        Self {
+            base_url: base_url.into(),
+            blocking: std::sync::OnceLock::new(),
+            async_client: reqwest::Client::new(),
+            retry,
+        }
+    }
+
+    fn blocking_client(&self) -> &reqwest::blocking::Client {
+        self.blocking.get_or_init(reqwest::blocking::Client::new)
+    }
+}
+
+/// A `reqwest::Error` worth retrying: one where a retry has a realistic
+/// chance of succeeding (timeout, connection failure, or a 5xx response),
+/// as opposed to a client error or a malformed request.
+fn is_transient(error: &reqwest::Error) -> bool {
+    error.is_timeout()
+        || error.is_connect()
+        || error.status().is_some_and(|status| status.is_server_error())
+}
+
+fn retry_sync<T>(retry: &RetryConfig, mut attempt: impl FnMut() -> Result<T, reqwest::Error>) -> ClientResult<T> {
+    let mut backoff = retry.initial_backoff;
+    for attempt_num in 1..=retry.max_attempts {
+        match attempt() {
+            Ok(value) => return Ok(value),
+            Err(e) if attempt_num < retry.max_attempts && is_transient(&e) => {
+                std::thread::sleep(backoff);
+                backoff *= 2;
+            }
+            Err(e) => return Err(Box::new(e)),
+        }
+    }
+    unreachable!("max_attempts must be at least 1")
+}
+
+async fn retry_async<T, F>(retry: &RetryConfig, mut attempt: impl FnMut() -> F) -> ClientResult<T>
+where
+    F: std::future::Future<Output = Result<T, reqwest::Error>>,
+{
+    let mut backoff = retry.initial_backoff;
+    for attempt_num in 1..=retry.max_attempts {
+        match attempt().await {
+            Ok(value) => return Ok(value),
+            Err(e) if attempt_num < retry.max_attempts && is_transient(&e) => {
+                tokio::time::sleep(backoff).await;
+                backoff *= 2;
+            }
+            Err(e) => return Err(Box::new(e)),
+        }
+    }
+    unreachable!("max_attempts must be at least 1")
+}
+
+impl SyncClient for HttpClient {
+    fn optimize(&self, assembly: &str) -> ClientResult<String> {
+        let url = format!("{}/optimize", self.base_url);
+        let body: OptimizeResponseBody = retry_sync(&self.retry, || {
+            self.blocking_client()
+                .post(&url)
+                .json(&OptimizeRequestBody { assembly: assembly.to_string() })
+                .send()?
+                .error_for_status()?
+                .json()
+        })?;
+        Ok(body.optimized_assembly)
+    }
+
+    fn optimize_batch(&self, items: &[String]) -> ClientResult<Vec<String>> {
+        let url = format!("{}/optimize/batch", self.base_url);
+        let request = BatchOptimizeRequestBody {
+            items: items.iter().map(|assembly| OptimizeRequestBody { assembly: assembly.clone() }).collect(),
+        };
+        let body: BatchOptimizeResponseBody = retry_sync(&self.retry, || {
+            self.blocking_client()
+                .post(&url)
+                .json(&request)
+                .send()?
+                .error_for_status()?
+                .json()
+        })?;
+        Ok(body.items.into_iter().map(|item| item.optimized_assembly).collect())
+    }
+}
+
+#[async_trait]
+impl AsyncClient for HttpClient {
+    async fn optimize(&self, assembly: &str) -> ClientResult<String> {
+        let url = format!("{}/optimize", self.base_url);
+        let body: OptimizeResponseBody = retry_async(&self.retry, || async {
+            self.async_client
+                .post(&url)
+                .json(&OptimizeRequestBody { assembly: assembly.to_string() })
+                .send()
+                .await?
+                .error_for_status()?
+                .json()
+                .await
+        })
+        .await?;
+        Ok(body.optimized_assembly)
+    }
+
+    async fn optimize_batch(&self, items: &[String]) -> ClientResult<Vec<String>> {
+        let url = format!("{}/optimize/batch", self.base_url);
+        let request = BatchOptimizeRequestBody {
+            items: items.iter().map(|assembly| OptimizeRequestBody { assembly: assembly.clone() }).collect(),
+        };
+        let body: BatchOptimizeResponseBody = retry_async(&self.retry, || async {
+            self.async_client
+                .post(&url)
+                .json(&request)
+                .send()
+                .await?
+                .error_for_status()?
+                .json()
+                .await
+        })
+        .await?;
+        Ok(body.items.into_iter().map(|item| item.optimized_assembly).collect())
+    }
+}
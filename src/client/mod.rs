@@ -0,0 +1,32 @@
+pub mod http;
+
+use async_trait::async_trait;
+
+/// Error type for client operations. Boxed as `Send + Sync` rather than the
+/// crate's usual bare `Box<dyn std::error::Error>` because [`AsyncClient`]'s
+/// `async_trait`-generated futures must be `Send`.
+pub type ClientError = Box<dyn std::error::Error + Send + Sync>;
+pub type ClientResult<T> = Result<T, ClientError>;
+
+/// Blocking calls against a Neurassembly server.
+pub trait SyncClient {
+    fn optimize(&self, assembly: &str) -> ClientResult<String>;
+    fn optimize_batch(&self, items: &[String]) -> ClientResult<Vec<String>>;
+}
+
+/// `async` equivalents of [`SyncClient`], for callers already inside a tokio
+/// runtime.
+#[async_trait]
+pub trait AsyncClient {
+    async fn optimize(&self, assembly: &str) -> ClientResult<String>;
+    async fn optimize_batch(&self, items: &[String]) -> ClientResult<Vec<String>>;
+}
+
+/// A client that supports both calling styles. Implemented automatically for
+/// any type that implements both halves.
+pub trait Client: SyncClient + AsyncClient {}
+
+impl<T: SyncClient + AsyncClient> Client for T {}
+
+// Re-export main types
+pub use http::HttpClient;
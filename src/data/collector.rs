@@ -1,15 +1,15 @@
+use crate::data::corpus::ExampleSource;
 use crate::model::{
-    encoder::{AssemblyEncoder, AssemblyToken},
+    encoder::AssemblyEncoder,
     trainer::TrainingExample,
 };
 use std::{
+    collections::{HashMap, HashSet},
     path::{Path, PathBuf},
     fs,
-    io::{self, BufRead, BufReader},
+    io,
     process::Command,
 };
-use iced_x86::{Decoder, DecoderOptions};
-use rayon::prelude::*;
 use serde::{Serialize, Deserialize};
 
 /// Configuration for data collection
@@ -19,8 +19,12 @@ pub struct CollectorConfig {
     pub source_dir: PathBuf,
     /// Directory to store compiled binaries and assembly
     pub output_dir: PathBuf,
-    /// Optimization levels to collect (-O0, -O1, -O2, -O3)
-    pub optimization_levels: Vec<String>,
+    /// Low optimization level whose output becomes a training example's
+    /// input (e.g. `-O0`)
+    pub unoptimized_level: String,
+    /// High optimization level whose output becomes a training example's
+    /// target (e.g. `-O3`)
+    pub optimized_level: String,
     /// File extensions to process
     pub source_extensions: Vec<String>,
     /// Maximum number of parallel jobs
@@ -32,7 +36,8 @@ impl Default for CollectorConfig {
         Self {
             source_dir: PathBuf::from("sources"),
             output_dir: PathBuf::from("compiled"),
-            optimization_levels: vec!["-O0".to_string(), "-O2".to_string(), "-O3".to_string()],
+            unoptimized_level: "-O0".to_string(),
+            optimized_level: "-O3".to_string(),
             source_extensions: vec!["c".to_string(), "cpp".to_string()],
             max_jobs: num_cpus::get(),
         }
@@ -62,9 +67,11 @@ impl AssemblyCollector {
         let source_files = self.find_source_files()?;
         tracing::info!("Found {} source files", source_files.len());
 
-        // Process files in parallel
+        // Process each file in turn: process_source_file takes `&mut self`
+        // (it drives the shared encoder), so files can't be farmed out to a
+        // rayon pool without giving every worker its own encoder.
         let examples: Vec<TrainingExample> = source_files
-            .par_iter()
+            .iter()
             .flat_map(|source_file| {
                 self.process_source_file(source_file)
                     .unwrap_or_else(|e| {
@@ -85,7 +92,7 @@ impl AssemblyCollector {
             let entry = entry?;
             let path = entry.path();
             if path.is_file() {
-                if let Some(ext) = path.extension() {
+                if let Some(ext) = path.extension().and_then(|ext| ext.to_str()) {
                     if self.config.source_extensions.iter().any(|e| e == ext) {
                         files.push(path);
                     }
@@ -100,22 +107,21 @@ impl AssemblyCollector {
         let mut examples = Vec::new();
         let file_stem = source_file.file_stem().unwrap().to_str().unwrap();
 
-        // Compile with different optimization levels
-        for opt_level in &self.config.optimization_levels {
-            let asm_path = self.config.output_dir.join(format!("{}_{}.s", file_stem, opt_level));
-            let obj_path = self.config.output_dir.join(format!("{}_{}.o", file_stem, opt_level));
+        // Compile the same source at both ends of the optimization range so
+        // each function's unoptimized and optimized bodies can be aligned.
+        let unopt_path = self.config.output_dir.join(format!("{}_{}.s", file_stem, self.config.unoptimized_level));
+        let opt_path = self.config.output_dir.join(format!("{}_{}.s", file_stem, self.config.optimized_level));
 
-            // Compile to assembly
-            self.compile_to_assembly(source_file, &asm_path, opt_level)?;
+        self.compile_to_assembly(source_file, &unopt_path, &self.config.unoptimized_level)?;
+        self.compile_to_assembly(source_file, &opt_path, &self.config.optimized_level)?;
 
-            // Extract function pairs from assembly
-            let function_pairs = self.extract_function_pairs(&asm_path)?;
+        // Extract aligned (unoptimized, optimized) function pairs
+        let function_pairs = self.extract_function_pairs(source_file, &unopt_path, &opt_path)?;
 
-            // Create training examples from function pairs
-            for (unopt_func, opt_func) in function_pairs {
-                if let Ok(example) = self.create_training_example(&unopt_func, &opt_func) {
-                    examples.push(example);
-                }
+        // Create training examples from function pairs
+        for (unopt_func, opt_func) in function_pairs {
+            if let Ok(example) = self.create_training_example(&unopt_func, &opt_func) {
+                examples.push(example);
             }
         }
 
@@ -142,37 +148,42 @@ impl AssemblyCollector {
         Ok(())
     }
 
-    /// Extract function pairs from assembly file
-    fn extract_function_pairs(&self, asm_file: &Path) -> io::Result<Vec<(String, String)>> {
-        let file = fs::File::open(asm_file)?;
-        let reader = BufReader::new(file);
-        let mut pairs = Vec::new();
-        let mut current_function = String::new();
-        let mut in_function = false;
-
-        for line in reader.lines() {
-            let line = line?;
-            if line.starts_with('.') && line.contains(':') {
-                // New function starts
-                if in_function {
-                    // Store previous function
-                    if !current_function.is_empty() {
-                        pairs.push((current_function.clone(), current_function.clone()));
-                    }
-                    current_function.clear();
-                }
-                in_function = true;
-            }
+    /// Extract (unoptimized, optimized) function body pairs by aligning the
+    /// function symbols present in both assembly files. A function compiled
+    /// out at only one optimization level (e.g. inlined away at `-O3`) is
+    /// skipped with a warning rather than silently paired with itself.
+    fn extract_function_pairs(
+        &self,
+        source_file: &Path,
+        unopt_file: &Path,
+        opt_file: &Path,
+    ) -> io::Result<Vec<(String, String)>> {
+        let unopt_functions = parse_functions(unopt_file)?;
+        let opt_functions = parse_functions(opt_file)?;
 
-            if in_function {
-                current_function.push_str(&line);
-                current_function.push('\n');
+        let mut pairs = Vec::new();
+        for (name, unopt_body) in &unopt_functions {
+            match opt_functions.get(name) {
+                Some(opt_body) => pairs.push((unopt_body.clone(), opt_body.clone())),
+                None => tracing::warn!(
+                    "function `{}` compiled at {} but not {} for {}; skipping",
+                    name,
+                    self.config.unoptimized_level,
+                    self.config.optimized_level,
+                    source_file.display(),
+                ),
             }
         }
-
-        // Don't forget the last function
-        if in_function && !current_function.is_empty() {
-            pairs.push((current_function.clone(), current_function.clone()));
+        for name in opt_functions.keys() {
+            if !unopt_functions.contains_key(name) {
+                tracing::warn!(
+                    "function `{}` compiled at {} but not {} for {}; skipping",
+                    name,
+                    self.config.optimized_level,
+                    self.config.unoptimized_level,
+                    source_file.display(),
+                );
+            }
         }
 
         Ok(pairs)
@@ -198,4 +209,71 @@ impl AssemblyCollector {
     pub fn get_encoder_mut(&mut self) -> &mut AssemblyEncoder {
         &mut self.encoder
     }
+}
+
+impl ExampleSource for AssemblyCollector {
+    fn examples(&mut self) -> impl Iterator<Item = TrainingExample> {
+        self.collect()
+            .unwrap_or_else(|e| {
+                tracing::error!("failed to collect compiled examples: {}", e);
+                Vec::new()
+            })
+            .into_iter()
+    }
+}
+
+/// Parse an assembly file emitted by `gcc -S` into a map of function name to
+/// function body, using the symbol/label directives GCC actually emits
+/// rather than a generic "any line starting with `.`" heuristic. A function
+/// starts at a bare `name:` label for a symbol previously declared
+/// `.globl name`, and ends at the matching `.size name, .-name` directive
+/// (or, failing that, wherever the next function starts).
+fn parse_functions(asm_file: &Path) -> io::Result<HashMap<String, String>> {
+    let content = fs::read_to_string(asm_file)?;
+
+    let mut globals = HashSet::new();
+    for line in content.lines() {
+        let trimmed = line.trim();
+        if trimmed.starts_with(".globl") {
+            if let Some(name) = trimmed.split_whitespace().nth(1) {
+                globals.insert(name.to_string());
+            }
+        }
+    }
+
+    let mut functions = HashMap::new();
+    let mut current: Option<(String, String)> = None;
+
+    for line in content.lines() {
+        let trimmed = line.trim();
+
+        if let Some((name, body)) = current.as_mut() {
+            if trimmed.starts_with(".size") && trimmed.contains(name.as_str()) {
+                body.push_str(line);
+                body.push('\n');
+                functions.insert(name.clone(), body.clone());
+                current = None;
+                continue;
+            }
+        }
+
+        if let Some(label) = trimmed.strip_suffix(':') {
+            if globals.contains(label) {
+                if let Some((name, body)) = current.take() {
+                    functions.insert(name, body);
+                }
+                current = Some((label.to_string(), String::new()));
+            }
+        }
+
+        if let Some((_, body)) = current.as_mut() {
+            body.push_str(line);
+            body.push('\n');
+        }
+    }
+    if let Some((name, body)) = current {
+        functions.insert(name, body);
+    }
+
+    Ok(functions)
 } 
\ No newline at end of file
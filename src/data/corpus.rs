@@ -0,0 +1,209 @@
+use crate::data::preprocessor::DataPreprocessor;
+use crate::model::{encoder::AssemblyEncoder, trainer::TrainingExample};
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::{
+    fs::File,
+    io::{self, BufRead, BufReader},
+    path::PathBuf,
+    sync::Mutex,
+};
+
+/// A source of training examples, abstracting over how the (input, target)
+/// pair was produced — compiled locally at two optimization levels by
+/// [`AssemblyCollector`](crate::data::collector::AssemblyCollector), or read
+/// from a pre-built corpus shard by [`CorpusSource`]. Callers that build a
+/// training set can draw from any number of sources without caring which.
+pub trait ExampleSource {
+    fn examples(&mut self) -> impl Iterator<Item = TrainingExample>;
+}
+
+/// Configuration for ingesting a pre-built code/assembly corpus (Parquet or
+/// JSONL shards with a text column, in the style of public "the-stack"-like
+/// datasets) instead of compiling local sources.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CorpusConfig {
+    /// Glob pattern matching shard files, e.g. `"corpus/*.jsonl"` or
+    /// `"corpus/*.parquet"`. Shard format is inferred per-file from its
+    /// extension.
+    pub shard_glob: String,
+    /// Name of the column/field holding the source or assembly text.
+    pub content_field: String,
+    /// Name of the column/field holding a language tag, consulted by
+    /// `languages` filtering.
+    pub language_field: String,
+    /// Keep only records whose language tag matches one of these,
+    /// case-insensitively. Empty means no filtering.
+    pub languages: Vec<String>,
+    /// Keep at most this many records across all shards, counted after
+    /// language filtering and taken in shard order. Every shard is still
+    /// read in full before the cap is applied.
+    pub record_cap: Option<usize>,
+}
+
+impl Default for CorpusConfig {
+    fn default() -> Self {
+        Self {
+            shard_glob: "corpus/*.jsonl".to_string(),
+            content_field: "content".to_string(),
+            language_field: "language".to_string(),
+            languages: Vec::new(),
+            record_cap: None,
+        }
+    }
+}
+
+/// Reads a pre-built corpus of code/assembly snippets from Parquet or JSONL
+/// shards and turns each record into a [`TrainingExample`]. A corpus record
+/// is a single snippet rather than a matched optimization pair, so it's
+/// preprocessed and encoded once and used as both the input and the target;
+/// this gives the model broader exposure to real-world assembly/source
+/// forms alongside the genuine optimization pairs `AssemblyCollector`
+/// produces.
+pub struct CorpusSource {
+    config: CorpusConfig,
+    preprocessor: DataPreprocessor,
+}
+
+impl CorpusSource {
+    pub fn new(config: CorpusConfig) -> Self {
+        Self {
+            config,
+            preprocessor: DataPreprocessor::new(),
+        }
+    }
+
+    fn shard_paths(&self) -> io::Result<Vec<PathBuf>> {
+        let mut paths: Vec<PathBuf> = glob::glob(&self.config.shard_glob)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?
+            .filter_map(|entry| entry.ok())
+            .collect();
+        paths.sort();
+        Ok(paths)
+    }
+
+    /// Read every record's text content out of a single shard, already
+    /// filtered by `languages`. Format is chosen by file extension.
+    fn read_shard(&self, shard: &PathBuf) -> io::Result<Vec<String>> {
+        match shard.extension().and_then(|ext| ext.to_str()) {
+            Some("parquet") => self.read_parquet_shard(shard),
+            _ => self.read_jsonl_shard(shard),
+        }
+    }
+
+    fn read_jsonl_shard(&self, shard: &PathBuf) -> io::Result<Vec<String>> {
+        let file = File::open(shard)?;
+        let mut contents = Vec::new();
+
+        for line in BufReader::new(file).lines() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            let record: serde_json::Value = match serde_json::from_str(&line) {
+                Ok(record) => record,
+                Err(e) => {
+                    tracing::warn!("skipping malformed record in {}: {}", shard.display(), e);
+                    continue;
+                }
+            };
+
+            let language = record.get(&self.config.language_field).and_then(|v| v.as_str());
+            if !self.language_allowed(language) {
+                continue;
+            }
+
+            if let Some(content) = record.get(&self.config.content_field).and_then(|v| v.as_str()) {
+                contents.push(content.to_string());
+            }
+        }
+
+        Ok(contents)
+    }
+
+    fn read_parquet_shard(&self, shard: &PathBuf) -> io::Result<Vec<String>> {
+        use parquet::file::reader::{FileReader, SerializedFileReader};
+        use parquet::record::Field;
+
+        let file = File::open(shard)?;
+        let reader = SerializedFileReader::new(file).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        let row_iter = reader.get_row_iter(None).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+
+        let mut contents = Vec::new();
+        for row in row_iter {
+            let row = row.map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+
+            let mut content = None;
+            let mut language = None;
+            for (name, field) in row.get_column_iter() {
+                if name == &self.config.content_field {
+                    if let Field::Str(value) = field {
+                        content = Some(value.clone());
+                    }
+                } else if name == &self.config.language_field {
+                    if let Field::Str(value) = field {
+                        language = Some(value.clone());
+                    }
+                }
+            }
+
+            if !self.language_allowed(language.as_deref()) {
+                continue;
+            }
+            if let Some(content) = content {
+                contents.push(content);
+            }
+        }
+
+        Ok(contents)
+    }
+
+    fn language_allowed(&self, language: Option<&str>) -> bool {
+        if self.config.languages.is_empty() {
+            return true;
+        }
+        match language {
+            Some(language) => self.config.languages.iter().any(|allowed| allowed.eq_ignore_ascii_case(language)),
+            None => false,
+        }
+    }
+}
+
+impl ExampleSource for CorpusSource {
+    fn examples(&mut self) -> impl Iterator<Item = TrainingExample> {
+        let shard_paths = self.shard_paths().unwrap_or_else(|e| {
+            tracing::error!("failed to list shards matching `{}`: {}", self.config.shard_glob, e);
+            Vec::new()
+        });
+
+        let contents: Vec<String> = shard_paths
+            .par_iter()
+            .flat_map(|shard| {
+                self.read_shard(shard).unwrap_or_else(|e| {
+                    tracing::error!("failed to read shard {}: {}", shard.display(), e);
+                    Vec::new()
+                })
+            })
+            .collect();
+
+        let capped: Vec<String> = match self.config.record_cap {
+            Some(cap) => contents.into_iter().take(cap).collect(),
+            None => contents,
+        };
+
+        let encoder = Mutex::new(AssemblyEncoder::new());
+        capped
+            .par_iter()
+            .map(|content| {
+                let preprocessed = self.preprocessor.preprocess(content);
+                let tokens = encoder.lock().unwrap().encode(&preprocessed);
+                TrainingExample {
+                    input_tokens: tokens.clone(),
+                    target_tokens: tokens,
+                }
+            })
+            .collect::<Vec<_>>()
+            .into_iter()
+    }
+}
@@ -1,6 +1,8 @@
 pub mod collector;
+pub mod corpus;
 pub mod preprocessor;
 
 // Re-export main types
 pub use collector::AssemblyCollector;
-pub use preprocessor::DataPreprocessor; 
\ No newline at end of file
+pub use corpus::{CorpusConfig, CorpusSource, ExampleSource};
+pub use preprocessor::DataPreprocessor;
\ No newline at end of file
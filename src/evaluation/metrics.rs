@@ -1,9 +1,10 @@
-use crate::model::encoder::AssemblyToken;
+use crate::model::encoder::{AssemblyToken, TokenType};
 use std::{
     collections::HashMap,
     time::Duration,
     process::{Command, Stdio},
 };
+use raw_cpuid::CpuId;
 use serde::{Serialize, Deserialize};
 
 /// Performance metrics for assembly code
@@ -11,81 +12,423 @@ use serde::{Serialize, Deserialize};
 pub struct PerformanceMetrics {
     /// Number of instructions
     pub instruction_count: usize,
-    /// Estimated cycles
+    /// Final cycle estimate: `max(port_pressure_cycles, dependency_chain_cycles)`
     pub estimated_cycles: u64,
+    /// Lower bound from port contention: the most-loaded execution port's
+    /// summed reciprocal-throughput demand
+    pub port_pressure_cycles: u64,
+    /// Lower bound from the longest register read-after-write dependency
+    /// chain in the sequence
+    pub dependency_chain_cycles: u64,
     /// Memory operations
     pub memory_ops: usize,
     /// Register pressure (number of unique registers used)
     pub register_pressure: usize,
     /// Code size in bytes
     pub code_size: usize,
-    /// Execution time (if measured)
-    pub execution_time: Option<Duration>,
+    /// Execution time statistics (if measured)
+    pub execution_time: Option<ExecutionTimeStats>,
+}
+
+/// Statistical summary of repeated execution-time samples, after warmup
+/// iterations are discarded and outliers are filtered by a median-absolute-
+/// deviation test.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExecutionTimeStats {
+    pub mean: Duration,
+    pub median: Duration,
+    pub std_dev: Duration,
+    pub min: Duration,
+    /// 95% confidence interval for the mean, as `(lower, upper)`.
+    pub confidence_interval_95: (Duration, Duration),
+    /// Samples retained after MAD-based outlier filtering.
+    pub sample_count: usize,
+    /// Samples dropped as outliers.
+    pub outliers_dropped: usize,
+    /// One-time cost of compiling the assembly under test, kept separate
+    /// from the measured samples.
+    pub compilation_time: Duration,
+}
+
+/// Which bundled port/latency table to cost instructions against.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum MicroarchTable {
+    /// A generic Skylake-like table: 4-wide dispatch over ports 0/1/5/6 for
+    /// ALU ops, 2/3 for loads, 4/7 for stores.
+    SkylakeLike,
+    /// A generic Zen-like table: 4 ALU pipes (ports 0-3), 2 AGU pipes (4/5).
+    ZenLike,
+    /// Conservative single-latency-number table used when the host
+    /// microarchitecture can't be identified.
+    Generic,
 }
 
 /// Configuration for performance measurement
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MetricsConfig {
-    /// Number of times to run benchmarks
+    /// Number of timed samples to collect after warmup
     pub benchmark_iterations: usize,
+    /// Number of untimed warmup iterations run (and discarded) before
+    /// sampling begins
+    pub warmup_iterations: usize,
+    /// A sample more than this many median-absolute-deviations from the
+    /// median is dropped as an outlier before computing statistics
+    pub mad_outlier_threshold: f64,
     /// Whether to include execution time measurements
     pub measure_execution_time: bool,
     /// Temporary directory for compiled code
     pub temp_dir: std::path::PathBuf,
+    /// Force a specific port/latency table instead of auto-detecting the
+    /// host CPU, so evaluation results are reproducible across machines.
+    pub force_microarch_table: Option<MicroarchTable>,
 }
 
 impl Default for MetricsConfig {
     fn default() -> Self {
         Self {
             benchmark_iterations: 100,
+            warmup_iterations: 10,
+            mad_outlier_threshold: 3.0,
             measure_execution_time: true,
             temp_dir: std::env::temp_dir().join("neurassembly"),
+            force_microarch_table: None,
         }
     }
 }
 
+/// An execution port on a superscalar core. Eight ports are modeled; the
+/// Skylake-like table uses all of them, the Zen-like table ports 0-5, and
+/// the generic table only port 0.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ExecPort {
+    P0,
+    P1,
+    P2,
+    P3,
+    P4,
+    P5,
+    P6,
+    P7,
+}
+
+/// A fixed-size set of execution ports, backed by a bitmask since at most
+/// eight ports are ever modeled.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PortSet(u8);
+
+impl PortSet {
+    fn new(ports: &[ExecPort]) -> Self {
+        ports.iter().fold(Self::default(), |set, &port| set.with(port))
+    }
+
+    fn with(self, port: ExecPort) -> Self {
+        Self(self.0 | (1 << port as u8))
+    }
+
+    fn iter(self) -> impl Iterator<Item = ExecPort> {
+        [ExecPort::P0, ExecPort::P1, ExecPort::P2, ExecPort::P3, ExecPort::P4, ExecPort::P5, ExecPort::P6, ExecPort::P7]
+            .into_iter()
+            .filter(move |&port| self.0 & (1 << port as u8) != 0)
+    }
+}
+
+/// Port-pressure and latency characteristics for one mnemonic.
+#[derive(Debug, Clone, Copy)]
+struct InstructionCost {
+    /// Cycles before the result is available to a dependent instruction.
+    latency: u64,
+    /// Cycles of exclusive port occupancy per issue (may be fractional,
+    /// e.g. 0.5 for a double-pumped unit).
+    reciprocal_throughput: f64,
+    /// Ports this instruction can be issued to; port-pressure is split
+    /// evenly across all of these.
+    ports: PortSet,
+}
+
+fn skylake_like_costs() -> HashMap<&'static str, InstructionCost> {
+    use ExecPort::*;
+    let mut costs = HashMap::new();
+    let mut add = |name, latency, rtp, ports: &[ExecPort]| {
+        costs.insert(name, InstructionCost { latency, reciprocal_throughput: rtp, ports: PortSet::new(ports) });
+    };
+    add("mov", 1, 0.25, &[P0, P1, P5, P6]);
+    add("add", 1, 0.25, &[P0, P1, P5, P6]);
+    add("sub", 1, 0.25, &[P0, P1, P5, P6]);
+    add("inc", 1, 0.25, &[P0, P1, P5, P6]);
+    add("dec", 1, 0.25, &[P0, P1, P5, P6]);
+    add("and", 1, 0.25, &[P0, P1, P5, P6]);
+    add("or", 1, 0.25, &[P0, P1, P5, P6]);
+    add("xor", 1, 0.25, &[P0, P1, P5, P6]);
+    add("not", 1, 0.25, &[P0, P1, P5, P6]);
+    add("neg", 1, 0.25, &[P0, P1, P5, P6]);
+    add("cmp", 1, 0.25, &[P0, P1, P5, P6]);
+    add("test", 1, 0.25, &[P0, P1, P5, P6]);
+    add("lea", 1, 0.5, &[P1, P5]);
+    add("shl", 1, 0.5, &[P0, P6]);
+    add("shr", 1, 0.5, &[P0, P6]);
+    add("sar", 1, 0.5, &[P0, P6]);
+    add("push", 1, 1.0, &[P2, P3, P4, P7]);
+    add("pop", 1, 0.5, &[P2, P3]);
+    add("load", 5, 0.5, &[P2, P3]);
+    add("store", 1, 1.0, &[P4, P7]);
+    add("jmp", 1, 0.5, &[P0, P6]);
+    add("je", 1, 0.5, &[P0, P6]);
+    add("jne", 1, 0.5, &[P0, P6]);
+    add("call", 1, 1.0, &[P0, P6]);
+    add("ret", 1, 1.0, &[P0, P6]);
+    add("mul", 3, 1.0, &[P1]);
+    add("imul", 3, 1.0, &[P1]);
+    add("div", 36, 24.0, &[P0]);
+    add("idiv", 36, 24.0, &[P0]);
+    add("movaps", 1, 0.33, &[P0, P1, P5]);
+    add("addps", 4, 0.5, &[P0, P1]);
+    add("mulps", 4, 0.5, &[P0, P1]);
+    costs
+}
+
+fn zen_like_costs() -> HashMap<&'static str, InstructionCost> {
+    use ExecPort::*;
+    let mut costs = HashMap::new();
+    let mut add = |name, latency, rtp, ports: &[ExecPort]| {
+        costs.insert(name, InstructionCost { latency, reciprocal_throughput: rtp, ports: PortSet::new(ports) });
+    };
+    add("mov", 1, 0.25, &[P0, P1, P2, P3]);
+    add("add", 1, 0.25, &[P0, P1, P2, P3]);
+    add("sub", 1, 0.25, &[P0, P1, P2, P3]);
+    add("inc", 1, 0.25, &[P0, P1, P2, P3]);
+    add("dec", 1, 0.25, &[P0, P1, P2, P3]);
+    add("and", 1, 0.25, &[P0, P1, P2, P3]);
+    add("or", 1, 0.25, &[P0, P1, P2, P3]);
+    add("xor", 1, 0.25, &[P0, P1, P2, P3]);
+    add("not", 1, 0.25, &[P0, P1, P2, P3]);
+    add("neg", 1, 0.25, &[P0, P1, P2, P3]);
+    add("cmp", 1, 0.25, &[P0, P1, P2, P3]);
+    add("test", 1, 0.25, &[P0, P1, P2, P3]);
+    add("lea", 1, 0.33, &[P0, P1, P2]);
+    add("shl", 1, 0.5, &[P0, P2]);
+    add("shr", 1, 0.5, &[P0, P2]);
+    add("sar", 1, 0.5, &[P0, P2]);
+    add("push", 2, 1.0, &[P4, P5]);
+    add("pop", 2, 0.5, &[P4, P5]);
+    add("load", 4, 0.5, &[P4, P5]);
+    add("store", 1, 1.0, &[P4, P5]);
+    add("jmp", 1, 0.5, &[P0, P1]);
+    add("je", 1, 0.5, &[P0, P1]);
+    add("jne", 1, 0.5, &[P0, P1]);
+    add("call", 1, 1.0, &[P0, P1]);
+    add("ret", 1, 1.0, &[P0, P1]);
+    add("mul", 3, 1.0, &[P0, P1]);
+    add("imul", 3, 1.0, &[P0, P1]);
+    add("div", 16, 14.0, &[P0]);
+    add("idiv", 16, 14.0, &[P0]);
+    add("movaps", 1, 0.25, &[P0, P1, P2, P3]);
+    add("addps", 3, 0.5, &[P0, P1]);
+    add("mulps", 3, 0.5, &[P0, P1]);
+    costs
+}
+
+fn generic_costs() -> HashMap<&'static str, InstructionCost> {
+    let single_port = PortSet::new(&[ExecPort::P0]);
+    let costs = [
+        ("mov", 1), ("add", 1), ("sub", 1), ("inc", 1), ("dec", 1),
+        ("and", 1), ("or", 1), ("xor", 1), ("not", 1), ("neg", 1),
+        ("cmp", 1), ("test", 1), ("lea", 1), ("shl", 1), ("shr", 1), ("sar", 1),
+        ("push", 3), ("pop", 3), ("load", 4), ("store", 4),
+        ("jmp", 2), ("je", 2), ("jne", 2), ("call", 3), ("ret", 3),
+        ("mul", 3), ("imul", 3), ("div", 15), ("idiv", 15),
+        ("movaps", 1), ("addps", 3), ("mulps", 4),
+    ];
+    costs
+        .into_iter()
+        .map(|(name, latency)| (name, InstructionCost { latency, reciprocal_throughput: latency as f64, ports: single_port }))
+        .collect()
+}
+
+/// Detect the host CPU vendor via `cpuid` and pick a bundled port table.
+/// Falls back to [`MicroarchTable::Generic`] when the vendor can't be
+/// determined (e.g. running inside a hypervisor that masks the leaf).
+fn detect_host_microarch_table() -> MicroarchTable {
+    match CpuId::new().get_vendor_info() {
+        Some(vendor) if vendor.as_str() == "GenuineIntel" => MicroarchTable::SkylakeLike,
+        Some(vendor) if vendor.as_str() == "AuthenticAMD" => MicroarchTable::ZenLike,
+        _ => MicroarchTable::Generic,
+    }
+}
+
+fn costs_for_table(table: MicroarchTable) -> HashMap<&'static str, InstructionCost> {
+    match table {
+        MicroarchTable::SkylakeLike => skylake_like_costs(),
+        MicroarchTable::ZenLike => zen_like_costs(),
+        MicroarchTable::Generic => generic_costs(),
+    }
+}
+
+/// One decoded instruction, reduced to what the cost model needs: its
+/// mnemonic and the registers it reads/writes.
+struct CostedInstruction {
+    mnemonic: String,
+    defs: Vec<String>,
+    uses: Vec<String>,
+}
+
+/// Group a flat token stream into per-instruction windows and extract
+/// def/use registers. This is a cost-model-only approximation (e.g. it
+/// does not distinguish sub-register widths) and is independent of the
+/// exact-semantics interpreter used for equivalence checking.
+fn extract_costed_instructions(tokens: &[AssemblyToken]) -> Vec<CostedInstruction> {
+    let mut instructions = Vec::new();
+    let mut current: Option<(String, Vec<String>)> = None;
+
+    let flush = |current: Option<(String, Vec<String>)>, instructions: &mut Vec<CostedInstruction>| {
+        if let Some((mnemonic, registers)) = current {
+            let write_only = matches!(mnemonic.as_str(), "mov" | "lea" | "movzx" | "movsx" | "pop");
+            let mut defs = Vec::new();
+            let mut uses = Vec::new();
+            for (i, reg) in registers.into_iter().enumerate() {
+                if i == 0 && write_only {
+                    defs.push(reg);
+                } else if i == 0 {
+                    defs.push(reg.clone());
+                    uses.push(reg);
+                } else {
+                    uses.push(reg);
+                }
+            }
+            instructions.push(CostedInstruction { mnemonic, defs, uses });
+        }
+    };
+
+    for token in tokens {
+        match token.token_type {
+            TokenType::Mnemonic => {
+                flush(current.take(), &mut instructions);
+                current = Some((token.value.to_lowercase(), Vec::new()));
+            }
+            TokenType::Register => {
+                if let Some((_, registers)) = current.as_mut() {
+                    registers.push(register_family(&token.value));
+                }
+            }
+            _ => {}
+        }
+    }
+    flush(current, &mut instructions);
+
+    instructions
+}
+
+/// Collapse a register name to its 64-bit family (e.g. `EAX`/`AX`/`AL` all
+/// become `RAX`) so hazard tracking isn't fooled by sub-register aliases.
+fn register_family(name: &str) -> String {
+    const FAMILIES: &[(&str, &[&str])] = &[
+        ("RAX", &["RAX", "EAX", "AX", "AL", "AH"]),
+        ("RBX", &["RBX", "EBX", "BX", "BL", "BH"]),
+        ("RCX", &["RCX", "ECX", "CX", "CL", "CH"]),
+        ("RDX", &["RDX", "EDX", "DX", "DL", "DH"]),
+        ("RSI", &["RSI", "ESI", "SI", "SIL"]),
+        ("RDI", &["RDI", "EDI", "DI", "DIL"]),
+        ("RBP", &["RBP", "EBP", "BP", "BPL"]),
+        ("RSP", &["RSP", "ESP", "SP", "SPL"]),
+        ("R8", &["R8", "R8D", "R8W", "R8B"]),
+        ("R9", &["R9", "R9D", "R9W", "R9B"]),
+        ("R10", &["R10", "R10D", "R10W", "R10B"]),
+        ("R11", &["R11", "R11D", "R11W", "R11B"]),
+        ("R12", &["R12", "R12D", "R12W", "R12B"]),
+        ("R13", &["R13", "R13D", "R13W", "R13B"]),
+        ("R14", &["R14", "R14D", "R14W", "R14B"]),
+        ("R15", &["R15", "R15D", "R15W", "R15B"]),
+    ];
+    let upper = name.to_uppercase();
+    FAMILIES
+        .iter()
+        .find(|(_, aliases)| aliases.contains(&upper.as_str()))
+        .map(|(family, _)| family.to_string())
+        .unwrap_or(upper)
+}
+
 /// Evaluates assembly code performance
 pub struct PerformanceEvaluator {
     config: MetricsConfig,
-    instruction_costs: HashMap<String, u64>,
+    microarch_table: MicroarchTable,
+    instruction_costs: HashMap<&'static str, InstructionCost>,
 }
 
 impl PerformanceEvaluator {
     pub fn new(config: MetricsConfig) -> Self {
-        let mut evaluator = Self {
-            config,
-            instruction_costs: HashMap::new(),
-        };
-        evaluator.initialize_instruction_costs();
-        evaluator
-    }
-
-    /// Initialize estimated cycle costs for common instructions
-    fn initialize_instruction_costs(&mut self) {
-        let costs = [
-            // Basic arithmetic
-            ("mov", 1), ("add", 1), ("sub", 1), ("inc", 1), ("dec", 1),
-            ("and", 1), ("or", 1), ("xor", 1), ("not", 1),
-            // Memory operations
-            ("push", 3), ("pop", 3), ("load", 4), ("store", 4),
-            // Control flow
-            ("jmp", 2), ("je", 2), ("jne", 2), ("call", 3), ("ret", 3),
-            // Complex operations
-            ("mul", 3), ("div", 15), ("idiv", 15),
-            // SIMD operations
-            ("movaps", 1), ("addps", 3), ("mulps", 4),
-        ];
-
-        for (inst, cost) in costs {
-            self.instruction_costs.insert(inst.to_string(), cost);
+        let microarch_table = config.force_microarch_table.unwrap_or_else(detect_host_microarch_table);
+        let instruction_costs = costs_for_table(microarch_table);
+        Self { config, microarch_table, instruction_costs }
+    }
+
+    /// Which bundled port/latency table this evaluator is costing
+    /// instructions against (auto-detected, or forced via
+    /// [`MetricsConfig::force_microarch_table`]).
+    pub fn microarch_table(&self) -> MicroarchTable {
+        self.microarch_table
+    }
+
+    /// Estimate cycles as `max(port-pressure bound, dependency-chain bound)`.
+    ///
+    /// The port-pressure bound sums each instruction's reciprocal-throughput
+    /// demand onto every port it could issue to, splitting the demand evenly
+    /// across those candidate ports, then takes the most-loaded port's
+    /// total. The dependency-chain bound walks the sequence tracking the
+    /// last writer of each register family and propagates the longest
+    /// cumulative latency along read-after-write edges.
+    fn estimate_cycles(&self, instructions: &[CostedInstruction]) -> (u64, u64) {
+        let mut port_load: HashMap<ExecPort, f64> = HashMap::new();
+        let mut last_write_finish: HashMap<&str, u64> = HashMap::new();
+        let mut longest_chain: u64 = 0;
+
+        for inst in instructions {
+            let cost = self.instruction_costs.get(inst.mnemonic.as_str());
+            let (latency, reciprocal_throughput, ports) = match cost {
+                Some(c) => (c.latency, c.reciprocal_throughput, c.ports),
+                None => (1, 1.0, PortSet::new(&[ExecPort::P0])),
+            };
+
+            let port_list: Vec<ExecPort> = ports.iter().collect();
+            if !port_list.is_empty() {
+                let share = reciprocal_throughput / port_list.len() as f64;
+                for port in port_list {
+                    *port_load.entry(port).or_insert(0.0) += share;
+                }
+            }
+
+            let ready_at = inst
+                .uses
+                .iter()
+                .filter_map(|reg| last_write_finish.get(reg.as_str()).copied())
+                .max()
+                .unwrap_or(0);
+            let finish = ready_at + latency;
+            for def in &inst.defs {
+                last_write_finish.insert(def.as_str(), finish);
+            }
+            longest_chain = longest_chain.max(finish);
         }
+
+        let port_pressure_cycles = port_load.values().cloned().fold(0.0_f64, f64::max).ceil() as u64;
+        (port_pressure_cycles, longest_chain)
     }
 
-    /// Calculate metrics for a sequence of assembly tokens
+    /// Calculate metrics for a sequence of assembly tokens.
+    ///
+    /// `execution_time` is always `None` here: `tokens` alone can't be
+    /// compiled back into a runnable program, since
+    /// [`AssemblyEncoder::encode`](crate::model::encoder::AssemblyEncoder::encode)
+    /// drops assembler directives (`.global`, ...) that `main`'s entry point
+    /// depends on. A caller that has the original compilable assembly text
+    /// on hand can measure it directly with
+    /// [`measure_execution_time`](Self::measure_execution_time) and fold the
+    /// result into a comparison via
+    /// [`compare_metrics_with_execution_time`](Self::compare_metrics_with_execution_time).
     pub fn calculate_metrics(&self, tokens: &[AssemblyToken]) -> PerformanceMetrics {
         let mut metrics = PerformanceMetrics {
             instruction_count: 0,
             estimated_cycles: 0,
+            port_pressure_cycles: 0,
+            dependency_chain_cycles: 0,
             memory_ops: 0,
             register_pressure: 0,
             code_size: tokens.len(),
@@ -97,23 +440,23 @@ impl PerformanceEvaluator {
 
         for token in tokens {
             match token.token_type {
-                crate::model::encoder::TokenType::Mnemonic => {
+                TokenType::Mnemonic => {
                     metrics.instruction_count += 1;
-                    current_mnemonic = Some(token.value.to_lowercase());
-                    
-                    // Add estimated cycles
-                    if let Some(cost) = self.instruction_costs.get(&token.value.to_lowercase()) {
-                        metrics.estimated_cycles += cost;
+                    let mnemonic = token.value.to_lowercase();
+                    // push/pop access the stack even with a register operand.
+                    if mnemonic.contains("push") || mnemonic.contains("pop") {
+                        metrics.memory_ops += 1;
                     }
+                    current_mnemonic = Some(mnemonic);
                 }
-                crate::model::encoder::TokenType::Register => {
+                TokenType::Register => {
                     used_registers.insert(token.value.clone());
                 }
-                crate::model::encoder::TokenType::Memory => {
+                // One memory op per `[...]` operand, counted at its opening bracket.
+                TokenType::Memory if token.value == "[" => {
                     if let Some(mnemonic) = &current_mnemonic {
-                        if mnemonic.contains("mov") || mnemonic.contains("load") || 
-                           mnemonic.contains("store") || mnemonic.contains("push") || 
-                           mnemonic.contains("pop") {
+                        if mnemonic.contains("mov") || mnemonic.contains("load") ||
+                           mnemonic.contains("store") {
                             metrics.memory_ops += 1;
                         }
                     }
@@ -123,48 +466,192 @@ impl PerformanceEvaluator {
         }
 
         metrics.register_pressure = used_registers.len();
+
+        let instructions = extract_costed_instructions(tokens);
+        let (port_pressure_cycles, dependency_chain_cycles) = self.estimate_cycles(&instructions);
+        metrics.port_pressure_cycles = port_pressure_cycles;
+        metrics.dependency_chain_cycles = dependency_chain_cycles;
+        metrics.estimated_cycles = port_pressure_cycles.max(dependency_chain_cycles);
+
         metrics
     }
 
-    /// Measure actual execution time of compiled assembly
-    pub fn measure_execution_time(&self, assembly: &str) -> Result<Duration, Box<dyn std::error::Error>> {
+    /// Measure actual execution time of compiled assembly.
+    ///
+    /// Compilation happens once, up front, and is reported separately from
+    /// the measured samples. If `assembly` exports a callable `main` symbol,
+    /// timing happens in-process (a shared library is loaded and `main` is
+    /// called directly in a tight loop) so process-spawn overhead and OS
+    /// jitter don't dominate the result; otherwise we fall back to timing
+    /// `fork`/`exec` of a standalone executable. Either way, a configurable
+    /// number of warmup iterations are discarded, remaining samples are
+    /// filtered for outliers via a median-absolute-deviation test, and the
+    /// result reports the full distribution rather than a bare average.
+    pub fn measure_execution_time(&self, assembly: &str) -> Result<ExecutionTimeStats, Box<dyn std::error::Error>> {
         if !self.config.measure_execution_time {
-            return Ok(Duration::from_secs(0));
+            return Ok(ExecutionTimeStats {
+                mean: Duration::ZERO,
+                median: Duration::ZERO,
+                std_dev: Duration::ZERO,
+                min: Duration::ZERO,
+                confidence_interval_95: (Duration::ZERO, Duration::ZERO),
+                sample_count: 0,
+                outliers_dropped: 0,
+                compilation_time: Duration::ZERO,
+            });
         }
 
-        // Create temporary files
         std::fs::create_dir_all(&self.config.temp_dir)?;
         let asm_file = self.config.temp_dir.join("test.s");
-        let _obj_file = self.config.temp_dir.join("test.o");
-        let exe_file = self.config.temp_dir.join("test");
-
-        // Write assembly to file
         std::fs::write(&asm_file, assembly)?;
 
-        // Compile assembly
+        let compile_start = std::time::Instant::now();
+        let shared_lib = self.config.temp_dir.join("test.so");
+        let shared_build = Command::new("gcc")
+            .args(["-shared", "-o", shared_lib.to_str().unwrap(), asm_file.to_str().unwrap()])
+            .output()?;
+        let shared_compilation_time = compile_start.elapsed();
+
+        let (compilation_time, samples) = if shared_build.status.success() {
+            match self.sample_in_process(&shared_lib) {
+                Some(samples) => (shared_compilation_time, samples),
+                None => {
+                    let (exe_compilation_time, samples) = self.sample_out_of_process(&asm_file)?;
+                    (shared_compilation_time + exe_compilation_time, samples)
+                }
+            }
+        } else {
+            let (exe_compilation_time, samples) = self.sample_out_of_process(&asm_file)?;
+            (shared_compilation_time + exe_compilation_time, samples)
+        };
+
+        Ok(Self::summarize_samples(samples, compilation_time, self.config.mad_outlier_threshold))
+    }
+
+    /// Load `shared_lib` and time `main` in-process. Returns `None` if the
+    /// library can't be loaded or doesn't export a callable `main`, so the
+    /// caller can fall back to subprocess timing.
+    fn sample_in_process(&self, shared_lib: &std::path::Path) -> Option<Vec<Duration>> {
+        let library = unsafe { libloading::Library::new(shared_lib).ok()? };
+        let main: libloading::Symbol<unsafe extern "C" fn()> = unsafe { library.get(b"main").ok()? };
+
+        for _ in 0..self.config.warmup_iterations {
+            unsafe { main() };
+        }
+
+        let mut samples = Vec::with_capacity(self.config.benchmark_iterations);
+        for _ in 0..self.config.benchmark_iterations {
+            let start = std::time::Instant::now();
+            unsafe { main() };
+            samples.push(start.elapsed());
+        }
+
+        Some(samples)
+    }
+
+    /// Time a standalone executable by spawning it once per sample. Used
+    /// when the assembly under test isn't a shared-library-safe function
+    /// (e.g. it makes syscalls or expects to run as a whole program).
+    /// Returns the executable's compile time alongside the samples.
+    fn sample_out_of_process(&self, asm_file: &std::path::Path) -> Result<(Duration, Vec<Duration>), Box<dyn std::error::Error>> {
+        let exe_file = self.config.temp_dir.join("test");
+        let compile_start = std::time::Instant::now();
         Command::new("gcc")
-            .args(&["-o", exe_file.to_str().unwrap(), asm_file.to_str().unwrap()])
+            .args(["-o", exe_file.to_str().unwrap(), asm_file.to_str().unwrap()])
             .output()?;
+        let compilation_time = compile_start.elapsed();
 
-        // Run multiple times and take average
-        let mut total_time = Duration::new(0, 0);
+        for _ in 0..self.config.warmup_iterations {
+            Command::new(&exe_file).stdout(Stdio::null()).stderr(Stdio::null()).output()?;
+        }
+
+        let mut samples = Vec::with_capacity(self.config.benchmark_iterations);
         for _ in 0..self.config.benchmark_iterations {
             let start = std::time::Instant::now();
-            Command::new(&exe_file)
-                .stdout(Stdio::null())
-                .stderr(Stdio::null())
-                .output()?;
-            total_time += start.elapsed();
+            Command::new(&exe_file).stdout(Stdio::null()).stderr(Stdio::null()).output()?;
+            samples.push(start.elapsed());
         }
 
-        Ok(total_time / self.config.benchmark_iterations as u32)
+        Ok((compilation_time, samples))
     }
 
-    /// Compare two versions of assembly code
+    /// Drop samples more than `mad_threshold` median-absolute-deviations
+    /// from the median, then summarize what remains. `pub` so the filtering
+    /// and confidence-interval math can be exercised directly against
+    /// synthetic sample sets in tests, without spawning `gcc`.
+    pub fn summarize_samples(mut samples: Vec<Duration>, compilation_time: Duration, mad_threshold: f64) -> ExecutionTimeStats {
+        if samples.is_empty() {
+            return ExecutionTimeStats {
+                mean: Duration::ZERO,
+                median: Duration::ZERO,
+                std_dev: Duration::ZERO,
+                min: Duration::ZERO,
+                confidence_interval_95: (Duration::ZERO, Duration::ZERO),
+                sample_count: 0,
+                outliers_dropped: 0,
+                compilation_time,
+            };
+        }
+
+        samples.sort();
+        let total = samples.len();
+        let median_ns = median_of(&samples);
+        let deviations: Vec<f64> = samples.iter().map(|d| (d.as_nanos() as f64 - median_ns).abs()).collect();
+        let mut sorted_deviations = deviations.clone();
+        sorted_deviations.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        let mad = sorted_deviations[sorted_deviations.len() / 2];
+
+        let filtered: Vec<Duration> = if mad == 0.0 {
+            samples
+        } else {
+            samples
+                .into_iter()
+                .zip(deviations)
+                .filter(|(_, deviation)| deviation / mad <= mad_threshold)
+                .map(|(sample, _)| sample)
+                .collect()
+        };
+
+        let outliers_dropped = total - filtered.len();
+        let n = filtered.len().max(1);
+        let mean_ns = filtered.iter().map(|d| d.as_nanos() as f64).sum::<f64>() / n as f64;
+        let variance = filtered.iter().map(|d| (d.as_nanos() as f64 - mean_ns).powi(2)).sum::<f64>() / n as f64;
+        let std_dev_ns = variance.sqrt();
+        // Normal approximation to the 95% CI of the mean.
+        let margin = 1.96 * std_dev_ns / (n as f64).sqrt();
+
+        ExecutionTimeStats {
+            mean: Duration::from_nanos(mean_ns.round() as u64),
+            median: Duration::from_nanos(median_of(&filtered).round() as u64),
+            std_dev: Duration::from_nanos(std_dev_ns.round() as u64),
+            min: filtered.iter().min().copied().unwrap_or(Duration::ZERO),
+            confidence_interval_95: (
+                Duration::from_nanos((mean_ns - margin).max(0.0).round() as u64),
+                Duration::from_nanos((mean_ns + margin).round() as u64),
+            ),
+            sample_count: filtered.len(),
+            outliers_dropped,
+            compilation_time,
+        }
+    }
+
+    /// Compare two versions of assembly code.
+    ///
+    /// Both sides are metered via [`calculate_metrics`](Self::calculate_metrics),
+    /// which never measures execution time (see its doc comment), so
+    /// `execution_time_reduction`/`execution_time_significant` are always
+    /// `None` here. Use
+    /// [`compare_metrics_with_execution_time`](Self::compare_metrics_with_execution_time)
+    /// when real timing for both sides is available.
     pub fn compare_metrics(&self, original: &[AssemblyToken], optimized: &[AssemblyToken]) -> MetricsComparison {
         let original_metrics = self.calculate_metrics(original);
         let optimized_metrics = self.calculate_metrics(optimized);
 
+        let (execution_time_reduction, execution_time_significant) = execution_time_comparison(
+            original_metrics.execution_time.as_ref(),
+            optimized_metrics.execution_time.as_ref(),
+        );
+
         MetricsComparison {
             instruction_reduction: percentage_change(
                 original_metrics.instruction_count as u64,
@@ -186,17 +673,71 @@ impl PerformanceEvaluator {
                 original_metrics.code_size as u64,
                 optimized_metrics.code_size as u64,
             ),
-            execution_time_reduction: match (original_metrics.execution_time, optimized_metrics.execution_time) {
-                (Some(original), Some(optimized)) => {
-                    Some(percentage_change(
-                        original.as_nanos() as u64,
-                        optimized.as_nanos() as u64,
-                    ))
-                }
-                _ => None,
-            },
+            execution_time_reduction,
+            execution_time_significant,
         }
     }
+
+    /// Compare two versions of assembly code the same way as
+    /// [`compare_metrics`](Self::compare_metrics), but fold in
+    /// `original_time`/`optimized_time` (e.g. from calling
+    /// [`measure_execution_time`](Self::measure_execution_time) on each
+    /// side's real, compilable source) so the returned comparison reports a
+    /// real `execution_time_reduction` and whether it's statistically
+    /// significant, rather than leaving those fields unset.
+    pub fn compare_metrics_with_execution_time(
+        &self,
+        original: &[AssemblyToken],
+        optimized: &[AssemblyToken],
+        original_time: &ExecutionTimeStats,
+        optimized_time: &ExecutionTimeStats,
+    ) -> MetricsComparison {
+        let mut comparison = self.compare_metrics(original, optimized);
+        let (execution_time_reduction, execution_time_significant) =
+            execution_time_comparison(Some(original_time), Some(optimized_time));
+        comparison.execution_time_reduction = execution_time_reduction;
+        comparison.execution_time_significant = execution_time_significant;
+        comparison
+    }
+}
+
+/// Derive `(execution_time_reduction, execution_time_significant)` for a
+/// [`MetricsComparison`] from each side's optional [`ExecutionTimeStats`],
+/// or `(None, None)` if either side wasn't measured.
+fn execution_time_comparison(
+    original: Option<&ExecutionTimeStats>,
+    optimized: Option<&ExecutionTimeStats>,
+) -> (Option<f64>, Option<bool>) {
+    match (original, optimized) {
+        (Some(original), Some(optimized)) => (
+            Some(percentage_change(original.mean.as_nanos() as u64, optimized.mean.as_nanos() as u64)),
+            Some(confidence_intervals_disjoint(original, optimized)),
+        ),
+        _ => (None, None),
+    }
+}
+
+/// Median of a set of durations, in nanoseconds. Assumes `samples` is
+/// already sorted.
+fn median_of(samples: &[Duration]) -> f64 {
+    let len = samples.len();
+    if len == 0 {
+        return 0.0;
+    }
+    if len.is_multiple_of(2) {
+        (samples[len / 2 - 1].as_nanos() as f64 + samples[len / 2].as_nanos() as f64) / 2.0
+    } else {
+        samples[len / 2].as_nanos() as f64
+    }
+}
+
+/// Whether two execution-time distributions' 95% confidence intervals fail
+/// to overlap, used as a simple significance test for "is this actually
+/// faster" without pulling in a full t-distribution implementation.
+pub fn confidence_intervals_disjoint(a: &ExecutionTimeStats, b: &ExecutionTimeStats) -> bool {
+    let (a_lo, a_hi) = a.confidence_interval_95;
+    let (b_lo, b_hi) = b.confidence_interval_95;
+    a_hi < b_lo || b_hi < a_lo
 }
 
 /// Comparison between original and optimized metrics
@@ -214,6 +755,9 @@ pub struct MetricsComparison {
     pub code_size_reduction: f64,
     /// Percentage reduction in execution time (if measured)
     pub execution_time_reduction: Option<f64>,
+    /// Whether the execution time improvement is statistically significant
+    /// (the original and optimized 95% confidence intervals don't overlap)
+    pub execution_time_significant: Option<bool>,
 }
 
 /// Calculate percentage change between two values
@@ -1,6 +1,8 @@
 pub mod metrics;
+pub mod summary;
 pub mod validator;
 
 // Re-export main types
 pub use metrics::PerformanceMetrics;
-pub use validator::OptimizationValidator; 
\ No newline at end of file
+pub use summary::RunSummary;
+pub use validator::OptimizationValidator;
\ No newline at end of file
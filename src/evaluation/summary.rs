@@ -0,0 +1,160 @@
+use crate::evaluation::metrics::MetricsComparison;
+use serde::{Serialize, Deserialize};
+use std::fmt;
+
+/// A labeled example retained for the best/worst cycle-reduction slots in a
+/// [`RunSummary`], so a report can point back at what produced the extreme.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RunExample {
+    pub label: String,
+    pub cycle_reduction: f64,
+}
+
+/// Incrementally accumulates [`MetricsComparison`] results across a
+/// training or evaluation run, without holding every example in memory.
+///
+/// Feed it one comparison per validated optimization via [`RunSummary::record`]
+/// (and [`RunSummary::record_rejection`] for optimizations the
+/// [`OptimizationValidator`](crate::evaluation::validator::OptimizationValidator)
+/// found non-equivalent); the running aggregates and best/worst examples
+/// update in constant time per call, so the summary can be printed or
+/// checkpointed at any point during a long run.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RunSummary {
+    accepted_count: usize,
+    rejected_count: usize,
+    instruction_reduction_sum: f64,
+    cycle_reduction_sum: f64,
+    memory_ops_reduction_sum: f64,
+    register_pressure_change_sum: f64,
+    best: Option<RunExample>,
+    worst: Option<RunExample>,
+}
+
+impl Default for RunSummary {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RunSummary {
+    pub fn new() -> Self {
+        Self {
+            accepted_count: 0,
+            rejected_count: 0,
+            instruction_reduction_sum: 0.0,
+            cycle_reduction_sum: 0.0,
+            memory_ops_reduction_sum: 0.0,
+            register_pressure_change_sum: 0.0,
+            best: None,
+            worst: None,
+        }
+    }
+
+    /// Record a validated (semantically equivalent) optimization's metrics.
+    /// `label` identifies the example for the best/worst report, e.g. a
+    /// function name or a `"{epoch}:{index}"` position in the run.
+    pub fn record(&mut self, label: impl Into<String>, comparison: &MetricsComparison) {
+        self.accepted_count += 1;
+        self.instruction_reduction_sum += comparison.instruction_reduction;
+        self.cycle_reduction_sum += comparison.cycle_reduction;
+        self.memory_ops_reduction_sum += comparison.memory_ops_reduction;
+        self.register_pressure_change_sum += comparison.register_pressure_change;
+
+        let example = RunExample {
+            label: label.into(),
+            cycle_reduction: comparison.cycle_reduction,
+        };
+        if self.best.as_ref().is_none_or(|best| example.cycle_reduction > best.cycle_reduction) {
+            self.best = Some(example.clone());
+        }
+        if self.worst.as_ref().is_none_or(|worst| example.cycle_reduction < worst.cycle_reduction) {
+            self.worst = Some(example);
+        }
+    }
+
+    /// Record an optimization the validator rejected as non-equivalent. It
+    /// contributes to `rejected_count` but not the metric aggregates, since
+    /// there's no valid rewrite to measure.
+    pub fn record_rejection(&mut self) {
+        self.rejected_count += 1;
+    }
+
+    /// Accepted (semantically equivalent) optimizations recorded so far.
+    pub fn accepted_count(&self) -> usize {
+        self.accepted_count
+    }
+
+    /// Optimizations the validator rejected as non-equivalent.
+    pub fn rejected_count(&self) -> usize {
+        self.rejected_count
+    }
+
+    /// Total optimizations seen, accepted or rejected.
+    pub fn total_count(&self) -> usize {
+        self.accepted_count + self.rejected_count
+    }
+
+    /// Mean instruction-count reduction across accepted optimizations.
+    pub fn mean_instruction_reduction(&self) -> f64 {
+        self.mean(self.instruction_reduction_sum)
+    }
+
+    /// Mean estimated-cycle reduction across accepted optimizations.
+    pub fn mean_cycle_reduction(&self) -> f64 {
+        self.mean(self.cycle_reduction_sum)
+    }
+
+    /// Mean memory-operation reduction across accepted optimizations.
+    pub fn mean_memory_ops_reduction(&self) -> f64 {
+        self.mean(self.memory_ops_reduction_sum)
+    }
+
+    /// Mean register-pressure change across accepted optimizations.
+    pub fn mean_register_pressure_change(&self) -> f64 {
+        self.mean(self.register_pressure_change_sum)
+    }
+
+    /// The accepted example with the largest cycle reduction.
+    pub fn best_example(&self) -> Option<&RunExample> {
+        self.best.as_ref()
+    }
+
+    /// The accepted example with the smallest (or most negative) cycle
+    /// reduction.
+    pub fn worst_example(&self) -> Option<&RunExample> {
+        self.worst.as_ref()
+    }
+
+    fn mean(&self, sum: f64) -> f64 {
+        if self.accepted_count == 0 {
+            0.0
+        } else {
+            sum / self.accepted_count as f64
+        }
+    }
+}
+
+impl fmt::Display for RunSummary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "Run summary: {} accepted, {} rejected ({} total)",
+            self.accepted_count, self.rejected_count, self.total_count()
+        )?;
+        writeln!(f, "{:<28} {:>10}", "metric", "mean %")?;
+        writeln!(f, "{:<28} {:>10.2}", "instruction reduction", self.mean_instruction_reduction())?;
+        writeln!(f, "{:<28} {:>10.2}", "cycle reduction", self.mean_cycle_reduction())?;
+        writeln!(f, "{:<28} {:>10.2}", "memory ops reduction", self.mean_memory_ops_reduction())?;
+        writeln!(f, "{:<28} {:>10.2}", "register pressure change", self.mean_register_pressure_change())?;
+        match &self.best {
+            Some(example) => writeln!(f, "best example:  {} ({:.2}% cycle reduction)", example.label, example.cycle_reduction)?,
+            None => writeln!(f, "best example:  n/a")?,
+        }
+        match &self.worst {
+            Some(example) => write!(f, "worst example: {} ({:.2}% cycle reduction)", example.label, example.cycle_reduction)?,
+            None => write!(f, "worst example: n/a")?,
+        }
+        Ok(())
+    }
+}
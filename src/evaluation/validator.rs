@@ -1,9 +1,677 @@
-use crate::model::encoder::AssemblyToken;
-use std::collections::HashSet;
+use crate::model::encoder::{AssemblyToken, TokenType};
+use rand::Rng;
+use std::collections::{HashMap, HashSet};
+
+/// Number of randomized initial states tried per equivalence check, absent an
+/// explicit override via [`OptimizationValidator::with_trial_count`].
+const DEFAULT_TRIAL_COUNT: usize = 200;
+
+/// Number of random byte addresses seeded with random values in a generated
+/// state. The addresses are drawn independently of the sequences'
+/// `[base+index*scale+disp]` operands, and a read from any other address
+/// yields 0.
+const DEFAULT_MEMORY_POOL_SIZE: usize = 16;
+
+/// RFLAGS bits this interpreter models, as (name, bit index) pairs.
+const FLAG_BITS: &[(&str, u32)] = &[
+	("CF", 0),
+	("PF", 2),
+	("AF", 4),
+	("ZF", 6),
+	("SF", 7),
+	("OF", 11),
+];
+
+/// 64-bit register families and their sub-width aliases, as
+/// `(alias, width_in_bits)`. The first entry in each family is the canonical
+/// 64-bit name used as the `CpuState` storage key.
+const REGISTER_FAMILIES: &[&[(&str, u32)]] = &[
+	&[("RAX", 64), ("EAX", 32), ("AX", 16), ("AL", 8), ("AH", 8)],
+	&[("RBX", 64), ("EBX", 32), ("BX", 16), ("BL", 8), ("BH", 8)],
+	&[("RCX", 64), ("ECX", 32), ("CX", 16), ("CL", 8), ("CH", 8)],
+	&[("RDX", 64), ("EDX", 32), ("DX", 16), ("DL", 8), ("DH", 8)],
+	&[("RSI", 64), ("ESI", 32), ("SI", 16), ("SIL", 8)],
+	&[("RDI", 64), ("EDI", 32), ("DI", 16), ("DIL", 8)],
+	&[("RBP", 64), ("EBP", 32), ("BP", 16), ("BPL", 8)],
+	&[("RSP", 64), ("ESP", 32), ("SP", 16), ("SPL", 8)],
+	&[("R8", 64), ("R8D", 32), ("R8W", 16), ("R8B", 8)],
+	&[("R9", 64), ("R9D", 32), ("R9W", 16), ("R9B", 8)],
+	&[("R10", 64), ("R10D", 32), ("R10W", 16), ("R10B", 8)],
+	&[("R11", 64), ("R11D", 32), ("R11W", 16), ("R11B", 8)],
+	&[("R12", 64), ("R12D", 32), ("R12W", 16), ("R12B", 8)],
+	&[("R13", 64), ("R13D", 32), ("R13W", 16), ("R13B", 8)],
+	&[("R14", 64), ("R14D", 32), ("R14W", 16), ("R14B", 8)],
+	&[("R15", 64), ("R15D", 32), ("R15W", 16), ("R15B", 8)],
+];
+
+fn lookup_register(name: &str) -> Option<(&'static str, u32, bool)> {
+	for family in REGISTER_FAMILIES {
+		for &(alias, width) in *family {
+			if alias.eq_ignore_ascii_case(name) {
+				let is_high_byte = width == 8 && alias.ends_with('H');
+				return Some((family[0].0, width, is_high_byte));
+			}
+		}
+	}
+	None
+}
+
+/// The RFLAGS bits one interpreted instruction sets, bundled so
+/// [`CpuState::set_flags`] takes one argument instead of a bit per flag.
+#[derive(Debug, Clone, Copy, Default)]
+struct Flags {
+	cf: bool,
+	pf: bool,
+	af: bool,
+	zf: bool,
+	sf: bool,
+	of: bool,
+}
+
+/// A randomly generated, fully-specified machine state: general-purpose
+/// registers, RFLAGS, and a sparse byte-addressed memory map.
+#[derive(Debug, Clone, Default)]
+struct CpuState {
+	registers: HashMap<&'static str, u64>,
+	rflags: u64,
+	/// Bitmask over `FLAG_BITS` of which flag bits the most recent
+	/// flag-writing instruction actually defined (vs. left undefined).
+	flags_defined: u32,
+	memory: HashMap<u64, u8>,
+}
+
+impl CpuState {
+	fn random(rng: &mut impl Rng, memory_pool: &[u64]) -> Self {
+		let mut registers = HashMap::new();
+		for family in REGISTER_FAMILIES {
+			registers.insert(family[0].0, rng.gen::<u64>());
+		}
+		let mut memory = HashMap::new();
+		for &addr in memory_pool {
+			memory.insert(addr, rng.gen::<u8>());
+		}
+		Self {
+			registers,
+			rflags: rng.gen::<u64>(),
+			flags_defined: 0,
+			memory,
+		}
+	}
+
+	fn read_register(&self, name: &str) -> u64 {
+		let Some((family, width, is_high_byte)) = lookup_register(name) else {
+			return 0;
+		};
+		let value = *self.registers.get(family).unwrap_or(&0);
+		if is_high_byte {
+			(value >> 8) & 0xff
+		} else {
+			match width {
+				64 => value,
+				32 => value & 0xffff_ffff,
+				16 => value & 0xffff,
+				8 => value & 0xff,
+				_ => value,
+			}
+		}
+	}
+
+	fn write_register(&mut self, name: &str, value: u64) {
+		let Some((family, width, is_high_byte)) = lookup_register(name) else {
+			return;
+		};
+		let old = *self.registers.get(family).unwrap_or(&0);
+		let new = if is_high_byte {
+			(old & !0xff00) | ((value & 0xff) << 8)
+		} else {
+			match width {
+				// Writing a 32-bit GPR form zero-extends and clears the
+				// upper 32 bits, matching real x86-64 semantics.
+				64 => value,
+				32 => value & 0xffff_ffff,
+				16 => (old & !0xffff) | (value & 0xffff),
+				8 => (old & !0xff) | (value & 0xff),
+				_ => value,
+			}
+		};
+		self.registers.insert(family, new);
+	}
+
+	fn read_memory(&self, addr: u64, size_bytes: u32) -> u64 {
+		let mut value: u64 = 0;
+		for i in 0..size_bytes {
+			let byte = *self.memory.get(&(addr.wrapping_add(i as u64))).unwrap_or(&0);
+			value |= (byte as u64) << (8 * i);
+		}
+		value
+	}
+
+	fn write_memory(&mut self, addr: u64, size_bytes: u32, value: u64) {
+		for i in 0..size_bytes {
+			let byte = ((value >> (8 * i)) & 0xff) as u8;
+			self.memory.insert(addr.wrapping_add(i as u64), byte);
+		}
+	}
+
+	fn set_flags(&mut self, flags: Flags, defined: &[&str]) {
+		let bits = [
+			("CF", flags.cf),
+			("PF", flags.pf),
+			("AF", flags.af),
+			("ZF", flags.zf),
+			("SF", flags.sf),
+			("OF", flags.of),
+		];
+		let mut mask = 0u32;
+		for (name, bit) in bits {
+			let (_, index) = FLAG_BITS.iter().find(|(n, _)| *n == name).unwrap();
+			if bit {
+				self.rflags |= 1 << index;
+			} else {
+				self.rflags &= !(1u64 << index);
+			}
+			if defined.contains(&name) {
+				mask |= 1 << index;
+			}
+		}
+		self.flags_defined = mask;
+	}
+}
+
+/// A single parsed instruction: mnemonic plus its operand tokens, still
+/// grouped by the lexical shape the encoder produced (registers, a memory
+/// group, or an immediate, separated by `,` separators).
+struct ParsedInstruction<'a> {
+	mnemonic: String,
+	operands: Vec<Operand<'a>>,
+}
+
+enum Operand<'a> {
+	Register(&'a str),
+	Immediate(i64),
+	Memory {
+		base: Option<&'a str>,
+		index: Option<&'a str>,
+		scale: i64,
+		disp: i64,
+	},
+}
+
+fn parse_immediate(s: &str) -> i64 {
+	if let Some(hex) = s.strip_prefix("0x").or_else(|| s.strip_prefix("-0x")) {
+		let value = u64::from_str_radix(hex, 16).unwrap_or(0) as i64;
+		if s.starts_with('-') { -value } else { value }
+	} else {
+		s.parse::<i64>().unwrap_or(0)
+	}
+}
+
+/// Split a flat token stream into per-instruction groups, one per
+/// `Mnemonic` token. Any tokens preceding the first mnemonic (e.g. stray
+/// labels) are dropped; they carry no executable semantics for the
+/// interpreter.
+fn split_instructions(tokens: &[AssemblyToken]) -> Vec<&[AssemblyToken]> {
+	let mut groups = Vec::new();
+	let mut start = None;
+	for (i, token) in tokens.iter().enumerate() {
+		if token.token_type == TokenType::Mnemonic {
+			if let Some(s) = start {
+				groups.push(&tokens[s..i]);
+			}
+			start = Some(i);
+		}
+	}
+	if let Some(s) = start {
+		groups.push(&tokens[s..]);
+	}
+	groups
+}
+
+fn parse_instruction(tokens: &[AssemblyToken]) -> Option<ParsedInstruction<'_>> {
+	let mnemonic = tokens.first()?.value.to_lowercase();
+	let mut operands = Vec::new();
+	let mut i = 1;
+	while i < tokens.len() {
+		match tokens[i].token_type {
+			TokenType::Separator if tokens[i].value == "," => {
+				i += 1;
+			}
+			TokenType::Register => {
+				operands.push(Operand::Register(&tokens[i].value));
+				i += 1;
+			}
+			TokenType::Immediate => {
+				operands.push(Operand::Immediate(parse_immediate(&tokens[i].value)));
+				i += 1;
+			}
+			TokenType::Prefix => {
+				// Memory size prefix (e.g. "qword"); the following Memory
+				// "[" group carries the actual operand.
+				i += 1;
+			}
+			TokenType::Memory if tokens[i].value == "[" => {
+				i += 1;
+				let mut base = None;
+				let mut index = None;
+				let mut scale = 1;
+				let mut disp = 0;
+				while i < tokens.len() && !(tokens[i].token_type == TokenType::Memory && tokens[i].value == "]") {
+					match tokens[i].token_type {
+						TokenType::Register if base.is_none() && index.is_none() => {
+							base = Some(tokens[i].value.as_str());
+						}
+						TokenType::Register => {
+							index = Some(tokens[i].value.as_str());
+						}
+						TokenType::Immediate => {
+							if index.is_some() && scale == 1 && disp == 0 && tokens[i - 1].value == "*" {
+								scale = parse_immediate(&tokens[i].value);
+							} else {
+								disp = parse_immediate(&tokens[i].value);
+							}
+						}
+						_ => {}
+					}
+					i += 1;
+				}
+				i += 1; // consume "]"
+				operands.push(Operand::Memory { base, index, scale, disp });
+			}
+			_ => {
+				i += 1;
+			}
+		}
+	}
+	Some(ParsedInstruction { mnemonic, operands })
+}
+
+/// Outcome of executing a single sequence from a given initial state.
+#[derive(Debug, Clone, Default)]
+struct ExecutionOutcome {
+	/// Registers (by canonical 64-bit family name) that were the
+	/// destination of at least one write.
+	written_registers: HashSet<&'static str>,
+	final_state: FinalState,
+}
+
+#[derive(Debug, Clone, Default)]
+struct FinalState {
+	registers: HashMap<&'static str, u64>,
+	memory: HashMap<u64, u8>,
+	rflags: u64,
+	flags_defined: u32,
+}
+
+/// Instructions this interpreter understands. Anything outside this set
+/// aborts the trial rather than silently treating the rewrite as correct.
+fn execute_instruction(state: &mut CpuState, inst: &ParsedInstruction) -> Result<(), String> {
+	use Operand::*;
+
+	let read_operand = |state: &CpuState, op: &Operand, size: u32| -> u64 {
+		match op {
+			Register(r) => state.read_register(r),
+			Immediate(v) => *v as u64,
+			Memory { base, index, scale, disp } => {
+				let addr = effective_address(state, base, index, *scale, *disp);
+				state.read_memory(addr, size)
+			}
+		}
+	};
+
+	macro_rules! write_dst {
+		($state:expr, $dst:expr, $size:expr, $value:expr) => {
+			match $dst {
+				Register(r) => $state.write_register(r, $value),
+				Memory { base, index, scale, disp } => {
+					let addr = effective_address($state, base, index, *scale, *disp);
+					$state.write_memory(addr, $size, $value);
+				}
+				Immediate(_) => return Err("cannot write to an immediate operand".to_string()),
+			}
+		};
+	}
+
+	let size_of = |op: &Operand| -> u32 {
+		match op {
+			Register(r) => lookup_register(r).map(|(_, w, _)| w / 8).unwrap_or(8),
+			_ => 8,
+		}
+	};
+
+	match inst.mnemonic.as_str() {
+		"lea" => {
+			let (dst, src) = (&inst.operands[0], &inst.operands[1]);
+			let value = match src {
+				Memory { base, index, scale, disp } => effective_address(state, base, index, *scale, *disp),
+				_ => return Err("lea source must be a memory operand".to_string()),
+			};
+			write_dst!(state, dst, size_of(dst), value);
+		}
+		"mov" | "movzx" | "movsx" => {
+			let (dst, src) = (&inst.operands[0], &inst.operands[1]);
+			let size = size_of(dst);
+			let value = read_operand(state, src, size_of(src));
+			write_dst!(state, dst, size, value);
+		}
+		"add" => {
+			let (dst, src) = (&inst.operands[0], &inst.operands[1]);
+			let size = size_of(dst);
+			let a = read_operand(state, dst, size);
+			let b = read_operand(state, src, size);
+			let (result, cf, of) = add_with_flags(a, b, size);
+			write_dst!(state, dst, size, result);
+			state.set_flags(
+				Flags { cf, pf: parity(result), af: ((a ^ b ^ result) & 0x10) != 0, zf: result == 0, sf: sign_bit(result, size), of },
+				&["CF", "PF", "AF", "ZF", "SF", "OF"],
+			);
+		}
+		"sub" | "cmp" => {
+			let (dst, src) = (&inst.operands[0], &inst.operands[1]);
+			let size = size_of(dst);
+			let a = read_operand(state, dst, size);
+			let b = read_operand(state, src, size);
+			let (result, cf, of) = sub_with_flags(a, b, size);
+			if inst.mnemonic != "cmp" {
+				write_dst!(state, dst, size, result);
+			}
+			state.set_flags(
+				Flags { cf, pf: parity(result), af: ((a ^ b ^ result) & 0x10) != 0, zf: result == 0, sf: sign_bit(result, size), of },
+				&["CF", "PF", "AF", "ZF", "SF", "OF"],
+			);
+		}
+		"inc" | "dec" => {
+			let dst = &inst.operands[0];
+			let size = size_of(dst);
+			let a = read_operand(state, dst, size);
+			let delta = if inst.mnemonic == "inc" { 1i64 } else { -1i64 };
+			let (result, _cf, of) = if delta > 0 { add_with_flags(a, 1, size) } else { sub_with_flags(a, 1, size) };
+			write_dst!(state, dst, size, result);
+			// inc/dec do not touch CF.
+			state.set_flags(
+				Flags {
+					cf: state.rflags & 1 != 0,
+					pf: parity(result),
+					af: ((a ^ 1u64 ^ result) & 0x10) != 0,
+					zf: result == 0,
+					sf: sign_bit(result, size),
+					of,
+				},
+				&["PF", "AF", "ZF", "SF", "OF"],
+			);
+		}
+		"and" | "or" | "xor" => {
+			let (dst, src) = (&inst.operands[0], &inst.operands[1]);
+			let size = size_of(dst);
+			let a = read_operand(state, dst, size);
+			let b = read_operand(state, src, size);
+			let result = mask(match inst.mnemonic.as_str() {
+				"and" => a & b,
+				"or" => a | b,
+				_ => a ^ b,
+			}, size);
+			write_dst!(state, dst, size, result);
+			state.set_flags(
+				Flags { cf: false, pf: parity(result), af: false, zf: result == 0, sf: sign_bit(result, size), of: false },
+				&["CF", "PF", "ZF", "SF", "OF"],
+			);
+		}
+		"not" => {
+			let dst = &inst.operands[0];
+			let size = size_of(dst);
+			let a = read_operand(state, dst, size);
+			write_dst!(state, dst, size, mask(!a, size));
+		}
+		"neg" => {
+			let dst = &inst.operands[0];
+			let size = size_of(dst);
+			let a = read_operand(state, dst, size);
+			let (result, cf, of) = sub_with_flags(0, a, size);
+			write_dst!(state, dst, size, result);
+			state.set_flags(
+				Flags { cf, pf: parity(result), af: (a & 0x10) != 0, zf: result == 0, sf: sign_bit(result, size), of },
+				&["CF", "PF", "AF", "ZF", "SF", "OF"],
+			);
+		}
+		"test" => {
+			let (a_op, b_op) = (&inst.operands[0], &inst.operands[1]);
+			let size = size_of(a_op);
+			let a = read_operand(state, a_op, size);
+			let b = read_operand(state, b_op, size);
+			let result = mask(a & b, size);
+			state.set_flags(
+				Flags { cf: false, pf: parity(result), af: false, zf: result == 0, sf: sign_bit(result, size), of: false },
+				&["CF", "PF", "ZF", "SF", "OF"],
+			);
+		}
+		"push" => {
+			let src = &inst.operands[0];
+			let value = read_operand(state, src, 8);
+			let rsp = state.read_register("RSP").wrapping_sub(8);
+			state.write_register("RSP", rsp);
+			state.write_memory(rsp, 8, value);
+		}
+		"pop" => {
+			let dst = &inst.operands[0];
+			let rsp = state.read_register("RSP");
+			let value = state.read_memory(rsp, 8);
+			write_dst!(state, dst, 8, value);
+			state.write_register("RSP", rsp.wrapping_add(8));
+		}
+		"shl" | "sal" | "shr" | "sar" => {
+			let (dst, src) = (&inst.operands[0], &inst.operands[1]);
+			let size = size_of(dst);
+			let a = read_operand(state, dst, size);
+			let shift = read_operand(state, src, size) & 0x3f;
+			let result = match inst.mnemonic.as_str() {
+				"shl" | "sal" => mask(a << shift, size),
+				"shr" => mask(a.checked_shr(shift as u32).unwrap_or(0), size),
+				_ => {
+					// Arithmetic right shift: sign-extend from the operand width.
+					let signed = sign_extend(a, size);
+					mask((signed >> shift.min(63)) as u64, size)
+				}
+			};
+			write_dst!(state, dst, size, result);
+			// Flags after a variable shift count are partly undefined; model
+			// only ZF/SF as defined, matching the documented CF/OF caveats.
+			state.set_flags(
+				Flags { cf: false, pf: false, af: false, zf: result == 0, sf: sign_bit(result, size), of: false },
+				&["ZF", "SF"],
+			);
+		}
+		"mul" | "imul" if inst.operands.len() == 1 => {
+			let src = &inst.operands[0];
+			let size = size_of(src);
+			let a = state.read_register("RAX") & mask(u64::MAX, size);
+			let b = read_operand(state, src, size);
+			let full = (a as u128) * (b as u128);
+			write_dst!(state, &Operand::Register("RAX"), size, mask(full as u64, size));
+			if size == 8 {
+				state.write_register("RDX", (full >> 64) as u64);
+			} else {
+				state.write_register("RDX", mask((full >> (size * 8)) as u64, size));
+			}
+			let overflowed = full >> (size * 8) != 0;
+			// mul/imul leave SF/ZF/AF/PF architecturally undefined.
+			state.set_flags(Flags { cf: overflowed, pf: false, af: false, zf: false, sf: false, of: overflowed }, &["CF", "OF"]);
+		}
+		"div" | "idiv" if inst.operands.len() == 1 => {
+			let src = &inst.operands[0];
+			let size = size_of(src);
+			let divisor = read_operand(state, src, size);
+			if divisor == 0 {
+				return Err("division by zero during trial execution".to_string());
+			}
+			let dividend = state.read_register("RAX") & mask(u64::MAX, size);
+			let quotient = mask(dividend / divisor, size);
+			let remainder = mask(dividend % divisor, size);
+			state.write_register("RAX", quotient);
+			state.write_register("RDX", remainder);
+			// All flags are architecturally undefined after div/idiv.
+			state.flags_defined = 0;
+		}
+		"jmp" | "je" | "jne" | "jg" | "jl" | "jge" | "jle" | "ja" | "jb" | "jae" | "jbe" | "call" | "ret" | "nop" => {
+			// Straight-line validation covers peephole-sized windows;
+			// control-flow instructions are treated as no-ops on
+			// architectural state (branch targets aren't resolvable from a
+			// bare token window).
+		}
+		other => {
+			return Err(format!("unsupported mnemonic in equivalence checker: {other}"));
+		}
+	}
+	Ok(())
+}
+
+fn effective_address(state: &CpuState, base: &Option<&str>, index: &Option<&str>, scale: i64, disp: i64) -> u64 {
+	let base_val = base.map(|r| state.read_register(r)).unwrap_or(0);
+	let index_val = index.map(|r| state.read_register(r)).unwrap_or(0);
+	base_val
+		.wrapping_add((index_val as i64).wrapping_mul(scale) as u64)
+		.wrapping_add(disp as u64)
+}
+
+fn mask(value: u64, size_bytes: u32) -> u64 {
+	if size_bytes >= 8 {
+		value
+	} else {
+		value & ((1u64 << (size_bytes * 8)) - 1)
+	}
+}
+
+fn sign_extend(value: u64, size_bytes: u32) -> i64 {
+	let bits = size_bytes * 8;
+	if bits >= 64 {
+		return value as i64;
+	}
+	let shift = 64 - bits;
+	((value << shift) as i64) >> shift
+}
+
+fn sign_bit(value: u64, size_bytes: u32) -> bool {
+	let bits = size_bytes * 8;
+	if bits >= 64 {
+		(value >> 63) & 1 != 0
+	} else {
+		(value >> (bits - 1)) & 1 != 0
+	}
+}
+
+fn parity(value: u64) -> bool {
+	(value as u8).count_ones().is_multiple_of(2)
+}
+
+fn add_with_flags(a: u64, b: u64, size: u32) -> (u64, bool, bool) {
+	let result = mask(a.wrapping_add(b), size);
+	let cf = mask(a, size).wrapping_add(mask(b, size)) > mask(u64::MAX, size);
+	let of = (sign_bit(a, size) == sign_bit(b, size)) && (sign_bit(result, size) != sign_bit(a, size));
+	(result, cf, of)
+}
+
+fn sub_with_flags(a: u64, b: u64, size: u32) -> (u64, bool, bool) {
+	let result = mask(a.wrapping_sub(b), size);
+	let cf = mask(a, size) < mask(b, size);
+	let of = (sign_bit(a, size) != sign_bit(b, size)) && (sign_bit(result, size) != sign_bit(a, size));
+	(result, cf, of)
+}
+
+/// Run a full instruction sequence from `initial`, returning the registers
+/// that were ever written and the resulting machine state.
+fn run_sequence(initial: &CpuState, tokens: &[AssemblyToken]) -> Result<ExecutionOutcome, String> {
+	let mut state = initial.clone();
+	let mut written = HashSet::new();
+
+	for group in split_instructions(tokens) {
+		let Some(inst) = parse_instruction(group) else {
+			continue;
+		};
+		record_destination(&inst, &mut written);
+		execute_instruction(&mut state, &inst)?;
+	}
+
+	Ok(ExecutionOutcome {
+		written_registers: written,
+		final_state: FinalState {
+			registers: state.registers.clone(),
+			memory: state.memory.clone(),
+			rflags: state.rflags,
+			flags_defined: state.flags_defined,
+		},
+	})
+}
+
+/// Mirrors `execute_instruction`'s notion of "destination operand" so the
+/// comparison set (`written_registers`) matches what was actually mutated.
+fn record_destination(inst: &ParsedInstruction, written: &mut HashSet<&'static str>) {
+	let dst_reg = |op: &Operand| -> Option<&'static str> {
+		match op {
+			Operand::Register(r) => lookup_register(r).map(|(family, _, _)| family),
+			_ => None,
+		}
+	};
+
+	match inst.mnemonic.as_str() {
+		"mov" | "lea" | "movzx" | "movsx" | "add" | "sub" | "and" | "or" | "xor" | "not" | "neg" | "inc" | "dec"
+		| "shl" | "sal" | "shr" | "sar" => {
+			if let Some(op) = inst.operands.first() {
+				if let Some(r) = dst_reg(op) {
+					written.insert(r);
+				}
+			}
+		}
+		"pop" => {
+			if let Some(op) = inst.operands.first() {
+				if let Some(r) = dst_reg(op) {
+					written.insert(r);
+				}
+			}
+			written.insert("RSP");
+		}
+		"push" => {
+			written.insert("RSP");
+		}
+		"mul" | "imul" if inst.operands.len() == 1 => {
+			written.insert("RAX");
+			written.insert("RDX");
+		}
+		"div" | "idiv" if inst.operands.len() == 1 => {
+			written.insert("RAX");
+			written.insert("RDX");
+		}
+		_ => {}
+	}
+}
+
+/// Details of the first randomized trial where original and optimized
+/// sequences disagreed, kept so failures are debuggable instead of a bare
+/// boolean.
+#[derive(Debug, Clone)]
+pub struct EquivalenceDivergence {
+	pub trial_index: usize,
+	pub description: String,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ValidationResult {
+	pub semantically_equivalent: bool,
+	pub performance_improved: bool,
+	/// Populated when `semantically_equivalent` is false because a trial
+	/// produced different observable state, or execution itself failed
+	/// (e.g. an unsupported mnemonic, or division by zero in a generated
+	/// state).
+	pub divergence: Option<EquivalenceDivergence>,
+}
 
 pub struct OptimizationValidator {
 	pub check_correctness: bool,
 	pub check_performance: bool,
+	/// Number of randomized initial states tried before declaring two
+	/// sequences equivalent.
+	pub trial_count: usize,
+	/// Number of random byte addresses seeded with random values in each
+	/// generated state; they are not placed at the addresses `[...]`
+	/// operands resolve to.
+	pub memory_pool_size: usize,
 }
 
 impl OptimizationValidator {
@@ -11,14 +679,23 @@ impl OptimizationValidator {
 		Self {
 			check_correctness: true,
 			check_performance: true,
+			trial_count: DEFAULT_TRIAL_COUNT,
+			memory_pool_size: DEFAULT_MEMORY_POOL_SIZE,
 		}
 	}
 
+	pub fn with_trial_count(mut self, trial_count: usize) -> Self {
+		self.trial_count = trial_count;
+		self
+	}
+
 	pub fn validate(&self, original: &[AssemblyToken], optimized: &[AssemblyToken]) -> ValidationResult {
 		let mut result = ValidationResult::default();
 
 		if self.check_correctness {
-			result.semantically_equivalent = self.check_semantic_equivalence(original, optimized);
+			let (equivalent, divergence) = self.check_semantic_equivalence(original, optimized);
+			result.semantically_equivalent = equivalent;
+			result.divergence = divergence;
 		}
 
 		if self.check_performance {
@@ -28,30 +705,130 @@ impl OptimizationValidator {
 		result
 	}
 
-	fn check_semantic_equivalence(&self, original: &[AssemblyToken], optimized: &[AssemblyToken]) -> bool {
-		// Basic semantic check (can be expanded)
-		let original_regs = self.extract_registers(original);
-		let optimized_regs = self.extract_registers(optimized);
-		
-		// Check if the same registers are modified
-		original_regs == optimized_regs
+	/// Runs `original` and `optimized` from the same `trial_count` randomly
+	/// generated initial states and compares only the observable final
+	/// state: registers either sequence actually wrote, plus any
+	/// memory cell either sequence touched (unwritten cells are guaranteed
+	/// to agree since both runs start from an identical snapshot), with
+	/// architecturally-undefined flag bits masked out of the comparison.
+	fn check_semantic_equivalence(
+		&self,
+		original: &[AssemblyToken],
+		optimized: &[AssemblyToken],
+	) -> (bool, Option<EquivalenceDivergence>) {
+		let mut rng = rand::thread_rng();
+
+		for trial in 0..self.trial_count {
+			let memory_pool: Vec<u64> = (0..self.memory_pool_size)
+				.map(|_| rng.gen_range(0..0x1000u64) * 8)
+				.collect();
+			let initial = CpuState::random(&mut rng, &memory_pool);
+
+			let original_outcome = match run_sequence(&initial, original) {
+				Ok(outcome) => outcome,
+				Err(e) => {
+					return (
+						false,
+						Some(EquivalenceDivergence {
+							trial_index: trial,
+							description: format!("original sequence failed to execute: {e}"),
+						}),
+					);
+				}
+			};
+			let optimized_outcome = match run_sequence(&initial, optimized) {
+				Ok(outcome) => outcome,
+				Err(e) => {
+					return (
+						false,
+						Some(EquivalenceDivergence {
+							trial_index: trial,
+							description: format!("optimized sequence failed to execute: {e}"),
+						}),
+					);
+				}
+			};
+
+			// Registers: compare every register EITHER sequence wrote. A
+			// register only the optimized version touches still needs
+			// checking -- nothing here proves the rewrite restored or
+			// never persisted that value, so silently ignoring it would
+			// let a rewrite that clobbers an extra register (e.g. a
+			// callee-saved one, in a snippet embedded in a larger
+			// function) pass as equivalent.
+			let written_registers = original_outcome
+				.written_registers
+				.iter()
+				.chain(optimized_outcome.written_registers.iter())
+				.copied()
+				.collect::<HashSet<_>>();
+			for reg in written_registers {
+				let original_value = original_outcome.final_state.registers.get(reg).copied().unwrap_or(0);
+				let optimized_value = optimized_outcome.final_state.registers.get(reg).copied().unwrap_or(0);
+				if original_value != optimized_value {
+					return (
+						false,
+						Some(EquivalenceDivergence {
+							trial_index: trial,
+							description: format!(
+								"register {reg} diverged: original={original_value:#x} optimized={optimized_value:#x}"
+							),
+						}),
+					);
+				}
+			}
+
+			// Memory: compare every address either side touched. Aliased
+			// operands naturally collapse to the same map key, so this
+			// also catches aliasing mistakes in a rewrite.
+			let touched_addresses = original_outcome
+				.final_state
+				.memory
+				.keys()
+				.chain(optimized_outcome.final_state.memory.keys())
+				.copied()
+				.collect::<HashSet<_>>();
+			for addr in touched_addresses {
+				let original_value = original_outcome.final_state.memory.get(&addr).copied().unwrap_or(0);
+				let optimized_value = optimized_outcome.final_state.memory.get(&addr).copied().unwrap_or(0);
+				if original_value != optimized_value {
+					return (
+						false,
+						Some(EquivalenceDivergence {
+							trial_index: trial,
+							description: format!(
+								"memory[{addr:#x}] diverged: original={original_value:#x} optimized={optimized_value:#x}"
+							),
+						}),
+					);
+				}
+			}
+
+			// Flags: compare only bits both sides actually defined.
+			let shared_defined_mask =
+				original_outcome.final_state.flags_defined & optimized_outcome.final_state.flags_defined;
+			if shared_defined_mask != 0 {
+				let original_flags = original_outcome.final_state.rflags & shared_defined_mask as u64;
+				let optimized_flags = optimized_outcome.final_state.rflags & shared_defined_mask as u64;
+				if original_flags != optimized_flags {
+					return (
+						false,
+						Some(EquivalenceDivergence {
+							trial_index: trial,
+							description: format!(
+								"flags diverged under mask {shared_defined_mask:#x}: original={original_flags:#x} optimized={optimized_flags:#x}"
+							),
+						}),
+					);
+				}
+			}
+		}
+
+		(true, None)
 	}
 
 	fn check_performance_improvement(&self, original: &[AssemblyToken], optimized: &[AssemblyToken]) -> bool {
 		// Basic performance check (can be expanded)
 		optimized.len() <= original.len()
 	}
-
-	fn extract_registers(&self, tokens: &[AssemblyToken]) -> HashSet<String> {
-		tokens.iter()
-			.filter(|token| token.token_type == crate::model::encoder::TokenType::Register)
-			.map(|token| token.value.clone())
-			.collect()
-	}
 }
-
-#[derive(Debug, Default)]
-pub struct ValidationResult {
-	pub semantically_equivalent: bool,
-	pub performance_improved: bool,
-}
\ No newline at end of file
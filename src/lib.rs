@@ -2,8 +2,11 @@ pub mod model;
 pub mod data;
 pub mod evaluation;
 pub mod api;
+pub mod client;
 
 // Re-export commonly used items
 pub use model::optimizer::OptimizationModel;
 pub use data::collector::AssemblyCollector;
-pub use evaluation::metrics::PerformanceMetrics; 
\ No newline at end of file
+pub use evaluation::metrics::PerformanceMetrics;
+pub use evaluation::summary::RunSummary;
+pub use client::{Client, HttpClient}; 
\ No newline at end of file
@@ -1,13 +1,13 @@
-use iced_x86::{Decoder, DecoderOptions, Instruction, Register, MemorySize, OpKind};
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct AssemblyToken {
     pub token_type: TokenType,
     pub value: String,
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 
 pub enum TokenType {
     Mnemonic,
@@ -17,6 +17,37 @@ pub enum TokenType {
     Prefix,
     Separator,
     Label,
+    /// Marks the end of one instruction and the start of the next, so a
+    /// flat token stream can be split back into instructions on decode.
+    InstructionBoundary,
+}
+
+/// 64-bit GPR family names and their sub-width aliases, used to recognize a
+/// bare operand as a register rather than an immediate or label reference.
+const REGISTER_NAMES: &[&str] = &[
+    "rax", "eax", "ax", "al", "ah",
+    "rbx", "ebx", "bx", "bl", "bh",
+    "rcx", "ecx", "cx", "cl", "ch",
+    "rdx", "edx", "dx", "dl", "dh",
+    "rsi", "esi", "si", "sil",
+    "rdi", "edi", "di", "dil",
+    "rbp", "ebp", "bp", "bpl",
+    "rsp", "esp", "sp", "spl",
+    "r8", "r8d", "r8w", "r8b",
+    "r9", "r9d", "r9w", "r9b",
+    "r10", "r10d", "r10w", "r10b",
+    "r11", "r11d", "r11w", "r11b",
+    "r12", "r12d", "r12w", "r12b",
+    "r13", "r13d", "r13w", "r13b",
+    "r14", "r14d", "r14w", "r14b",
+    "r15", "r15d", "r15w", "r15b",
+];
+
+/// Memory operand size keywords (e.g. `dword ptr [...]`).
+const SIZE_PREFIXES: &[&str] = &["byte", "word", "dword", "qword", "xmmword", "ymmword"];
+
+fn is_register(operand: &str) -> bool {
+    REGISTER_NAMES.iter().any(|r| r.eq_ignore_ascii_case(operand))
 }
 
 pub struct AssemblyEncoder {
@@ -34,124 +65,92 @@ impl AssemblyEncoder {
         }
     }
 
+    /// Tokenize Intel-syntax assembly text, one instruction (or bare label)
+    /// per line: `mnemonic op1, op2, ...`, operands being registers,
+    /// immediates, or `[base+index*scale+disp]` memory references with an
+    /// optional `size ptr` prefix. Lines are terminated by an
+    /// [`InstructionBoundary`](TokenType::InstructionBoundary) token so the
+    /// flat stream can be split back into instructions downstream (see
+    /// [`crate::model::optimizer::split_instructions`]).
     pub fn encode(&mut self, assembly: &str) -> Vec<AssemblyToken> {
-        let bytes = assembly.as_bytes();
-        let mut decoder = Decoder::with_ip(64, bytes, 0, DecoderOptions::NONE);
         let mut tokens = Vec::new();
 
-        // Process each instruction
-        let mut instruction = Instruction::default();
-        while decoder.can_decode() {
-            decoder.decode_out(&mut instruction);
-            
-            // Add mnemonic
-            tokens.push(AssemblyToken {
-                token_type: TokenType::Mnemonic,
-                value: format!("{:?}", instruction.mnemonic()),
-            });
-
-            // Process operands
-            for i in 0..instruction.op_count() {
-                if i > 0 {
-                    tokens.push(AssemblyToken {
-                        token_type: TokenType::Separator,
-                        value: ",".to_string(),
-                    });
-                }
+        for raw_line in assembly.lines() {
+            let line = strip_comment(raw_line).trim();
+            if line.is_empty() || line.starts_with('.') {
+                // Blank lines and assembler directives carry no instruction
+                // semantics for the optimizer.
+                continue;
+            }
 
-                match instruction.op_kind(i) {
-                    OpKind::Register => {
-                        tokens.push(AssemblyToken {
-                            token_type: TokenType::Register,
-                            value: format!("{:?}", instruction.op_register(i)),
-                        });
-                    }
-                    OpKind::Memory => {
-                        self.encode_memory_operand(&instruction, i, &mut tokens);
-                    }
-                    OpKind::Immediate8 | OpKind::Immediate16 | OpKind::Immediate32 | OpKind::Immediate64 => {
-                        tokens.push(AssemblyToken {
-                            token_type: TokenType::Immediate,
-                            value: format!("{:#x}", instruction.immediate(i)),
-                        });
-                    }
-                    _ => {}
-                }
+            if let Some(label) = line.strip_suffix(':') {
+                tokens.push(AssemblyToken { token_type: TokenType::Label, value: label.trim().to_string() });
+                tokens.push(boundary_token());
+                continue;
             }
+
+            encode_instruction(line, &mut tokens);
+            tokens.push(boundary_token());
         }
 
         tokens
     }
 
-    fn encode_memory_operand(&self, instruction: &Instruction, _operand_index: u32, tokens: &mut Vec<AssemblyToken>) {
-        // Handle memory access size prefix
-        let size = instruction.memory_size();
-        if size != MemorySize::Unknown {
-            tokens.push(AssemblyToken {
-                token_type: TokenType::Prefix,
-                value: format!("{:?}", size).to_lowercase(),
-            });
-        }
+    /// Reconstruct Intel-syntax assembly from a token stream produced by
+    /// [`encode`](Self::encode), one instruction per line.
+    pub fn decode(&self, tokens: &[AssemblyToken]) -> String {
+        tokens
+            .split(|token| token.token_type == TokenType::InstructionBoundary)
+            .map(Self::decode_instruction)
+            .filter(|line| !line.is_empty())
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
 
-        tokens.push(AssemblyToken {
-            token_type: TokenType::Memory,
-            value: "[".to_string(),
-        });
-
-        // Base register
-        if instruction.memory_base() != Register::None {
-            tokens.push(AssemblyToken {
-                token_type: TokenType::Register,
-                value: format!("{:?}", instruction.memory_base()),
-            });
-        }
+    fn decode_instruction(tokens: &[AssemblyToken]) -> String {
+        let Some(mnemonic) = tokens.first() else {
+            return String::new();
+        };
 
-        // Index register
-        if instruction.memory_index() != Register::None {
-            if instruction.memory_base() != Register::None {
-                tokens.push(AssemblyToken {
-                    token_type: TokenType::Separator,
-                    value: "+".to_string(),
-                });
-            }
-            tokens.push(AssemblyToken {
-                token_type: TokenType::Register,
-                value: format!("{:?}", instruction.memory_index()),
-            });
-
-            // Scale
-            let scale = instruction.memory_index_scale();
-            if scale > 1 {
-                tokens.push(AssemblyToken {
-                    token_type: TokenType::Separator,
-                    value: "*".to_string(),
-                });
-                tokens.push(AssemblyToken {
-                    token_type: TokenType::Immediate,
-                    value: scale.to_string(),
-                });
-            }
+        if mnemonic.token_type == TokenType::Label {
+            return format!("{}:", mnemonic.value);
         }
 
-        // Displacement
-        let displacement = instruction.memory_displacement32();
-        if displacement != 0 {
-            if instruction.memory_base() != Register::None || instruction.memory_index() != Register::None {
-                tokens.push(AssemblyToken {
-                    token_type: TokenType::Separator,
-                    value: "+".to_string(),
-                });
+        let mut operands = Vec::new();
+        let mut current_operand = String::new();
+        let mut in_memory = false;
+
+        for token in &tokens[1..] {
+            match &token.token_type {
+                TokenType::Separator if token.value == "," && !in_memory => {
+                    operands.push(std::mem::take(&mut current_operand));
+                }
+                TokenType::Memory if token.value == "[" => {
+                    in_memory = true;
+                    current_operand.push('[');
+                }
+                TokenType::Memory if token.value == "]" => {
+                    in_memory = false;
+                    current_operand.push(']');
+                }
+                TokenType::Prefix => {
+                    current_operand.push_str(&token.value);
+                    current_operand.push(' ');
+                }
+                TokenType::Register => current_operand.push_str(&token.value.to_lowercase()),
+                TokenType::Immediate | TokenType::Separator => current_operand.push_str(&token.value),
+                TokenType::Mnemonic | TokenType::Label | TokenType::Memory | TokenType::InstructionBoundary => {}
             }
-            tokens.push(AssemblyToken {
-                token_type: TokenType::Immediate,
-                value: format!("{:#x}", displacement),
-            });
+        }
+        if !current_operand.is_empty() {
+            operands.push(current_operand);
         }
 
-        tokens.push(AssemblyToken {
-            token_type: TokenType::Memory,
-            value: "]".to_string(),
-        });
+        if operands.is_empty() {
+            mnemonic.value.to_lowercase()
+        } else {
+            format!("{} {}", mnemonic.value.to_lowercase(), operands.join(", "))
+        }
     }
 
     pub fn get_vocabulary_size(&self) -> usize {
@@ -173,4 +172,139 @@ impl AssemblyEncoder {
     pub fn get_token(&self, id: usize) -> Option<&str> {
         self.reverse_vocabulary.get(&id).map(|s| s.as_str())
     }
-} 
\ No newline at end of file
+}
+
+impl Default for AssemblyEncoder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn boundary_token() -> AssemblyToken {
+    AssemblyToken { token_type: TokenType::InstructionBoundary, value: "\n".to_string() }
+}
+
+/// Strip a trailing `;`-delimited comment, if any.
+fn strip_comment(line: &str) -> &str {
+    match line.find(';') {
+        Some(i) => &line[..i],
+        None => line,
+    }
+}
+
+/// Tokenize one instruction line (`mnemonic` optionally followed by operands)
+/// and append its tokens to `tokens`, not including the trailing
+/// [`InstructionBoundary`](TokenType::InstructionBoundary).
+fn encode_instruction(line: &str, tokens: &mut Vec<AssemblyToken>) {
+    let (mnemonic, rest) = match line.split_once(char::is_whitespace) {
+        Some((mnemonic, rest)) => (mnemonic, rest.trim()),
+        None => (line, ""),
+    };
+
+    tokens.push(AssemblyToken { token_type: TokenType::Mnemonic, value: mnemonic.to_string() });
+
+    if rest.is_empty() {
+        return;
+    }
+
+    for (i, operand) in split_top_level_commas(rest).into_iter().enumerate() {
+        if i > 0 {
+            tokens.push(AssemblyToken { token_type: TokenType::Separator, value: ",".to_string() });
+        }
+        encode_operand(operand.trim(), tokens);
+    }
+}
+
+/// Split an instruction's operand text on top-level commas, keeping commas
+/// inside a `[...]` memory operand from splitting that operand apart.
+fn split_top_level_commas(rest: &str) -> Vec<&str> {
+    let mut operands = Vec::new();
+    let mut depth = 0i32;
+    let mut start = 0;
+    for (i, c) in rest.char_indices() {
+        match c {
+            '[' => depth += 1,
+            ']' => depth -= 1,
+            ',' if depth == 0 => {
+                operands.push(&rest[start..i]);
+                start = i + c.len_utf8();
+            }
+            _ => {}
+        }
+    }
+    operands.push(&rest[start..]);
+    operands
+}
+
+fn encode_operand(operand: &str, tokens: &mut Vec<AssemblyToken>) {
+    if let Some(bracket_start) = operand.find('[') {
+        let prefix = operand[..bracket_start].trim();
+        if let Some(size) = prefix.split_whitespace().next() {
+            if SIZE_PREFIXES.iter().any(|p| p.eq_ignore_ascii_case(size)) {
+                tokens.push(AssemblyToken { token_type: TokenType::Prefix, value: size.to_lowercase() });
+            }
+        }
+
+        let bracket_end = operand.rfind(']').unwrap_or(operand.len());
+        let body = &operand[bracket_start + 1..bracket_end];
+
+        tokens.push(AssemblyToken { token_type: TokenType::Memory, value: "[".to_string() });
+        encode_memory_body(body, tokens);
+        tokens.push(AssemblyToken { token_type: TokenType::Memory, value: "]".to_string() });
+        return;
+    }
+
+    tokens.push(encode_atom(operand));
+}
+
+/// Tokenize the contents of a `[...]` memory operand (e.g.
+/// `rax+rbx*4+0x10` or `rbp-0x8`) into alternating Register/Immediate
+/// components and `+`/`*` separators. A `-` is kept attached to the
+/// immediate that follows it (e.g. `-0x8`) rather than split out as its own
+/// separator, so it round-trips through [`AssemblyEncoder::decode`]
+/// unchanged.
+fn encode_memory_body(body: &str, tokens: &mut Vec<AssemblyToken>) {
+    let body: String = body.chars().filter(|c| !c.is_whitespace()).collect();
+
+    let mut component_start = 0;
+    let mut pending_separator: Option<char> = None;
+    let chars: Vec<char> = body.chars().collect();
+
+    let flush = |end: usize, component_start: &mut usize, pending_separator: &mut Option<char>, tokens: &mut Vec<AssemblyToken>| {
+        let piece = &chars[*component_start..end];
+        if !piece.is_empty() {
+            if let Some(sep) = pending_separator.take() {
+                tokens.push(AssemblyToken { token_type: TokenType::Separator, value: sep.to_string() });
+            }
+            encode_memory_piece(&piece.iter().collect::<String>(), tokens);
+        }
+        *component_start = end + 1;
+    };
+
+    for (i, &c) in chars.iter().enumerate() {
+        if c == '+' || c == '*' {
+            flush(i, &mut component_start, &mut pending_separator, tokens);
+            pending_separator = Some(c);
+        }
+    }
+    flush(chars.len(), &mut component_start, &mut pending_separator, tokens);
+}
+
+/// Tokenize a single memory-body component, splitting off an embedded `-`
+/// sign (e.g. `rbp-0x8`) into a separate register and a signed immediate.
+fn encode_memory_piece(piece: &str, tokens: &mut Vec<AssemblyToken>) {
+    if let Some(dash) = piece[1..].find('-').map(|i| i + 1) {
+        tokens.push(encode_atom(&piece[..dash]));
+        tokens.push(encode_atom(&piece[dash..]));
+    } else {
+        tokens.push(encode_atom(piece));
+    }
+}
+
+fn encode_atom(value: &str) -> AssemblyToken {
+    if is_register(value) {
+        AssemblyToken { token_type: TokenType::Register, value: value.to_string() }
+    } else {
+        AssemblyToken { token_type: TokenType::Immediate, value: value.to_string() }
+    }
+}
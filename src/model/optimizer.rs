@@ -1,5 +1,17 @@
-use crate::model::encoder::{AssemblyToken, TokenType};
+use crate::model::encoder::{AssemblyEncoder, AssemblyToken, TokenType};
+use ort::execution_providers::CUDA;
+use ort::session::Session;
+use ort::value::Tensor;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
 
+/// Which execution provider to run inference on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum InferenceDevice {
+    #[default]
+    Cpu,
+    Cuda(i32),
+}
 
 #[derive(Debug, Clone)]
 pub struct OptimizationConfig {
@@ -7,6 +19,13 @@ pub struct OptimizationConfig {
     pub enable_register_allocation: bool,
     pub enable_dead_code_elimination: bool,
     pub vocab_size: i64,
+    /// Path to an ONNX model implementing the learned optimizer. When unset,
+    /// `optimize` falls back to the peephole/dead-code-elimination path.
+    pub model_path: Option<PathBuf>,
+    /// Execution provider to run the ONNX session on.
+    pub device: InferenceDevice,
+    /// Upper bound on how many tokens `optimize_with_model` will decode.
+    pub max_decode_len: usize,
 }
 
 impl Default for OptimizationConfig {
@@ -16,6 +35,9 @@ impl Default for OptimizationConfig {
             enable_register_allocation: true,
             enable_dead_code_elimination: true,
             vocab_size: 1000, // Default vocabulary size
+            model_path: None,
+            device: InferenceDevice::Cpu,
+            max_decode_len: 256,
         }
     }
 }
@@ -24,17 +46,36 @@ pub struct OptimizationModel {
     config: OptimizationConfig,
 
     patterns: Vec<OptimizationPattern>,
+    session: Option<Session>,
+    encoder: AssemblyEncoder,
 }
 
+#[derive(Serialize, Deserialize)]
 struct OptimizationPattern {
     pattern: Vec<TokenType>,
+    /// Literal value each position must match exactly, for the token types
+    /// where the value carries the pattern's meaning (mnemonics and
+    /// registers); `None` matches any value of that position's type.
+    literal_match: Vec<Option<String>>,
     replacement: Vec<AssemblyToken>,
 }
 
 impl OptimizationModel {
-    pub fn new(config: OptimizationConfig) -> Self {
+    pub fn new(config: OptimizationConfig) -> Result<Self, Box<dyn std::error::Error>> {
         let patterns = Self::initialize_patterns();
-        Self { config, patterns }
+        let session = match &config.model_path {
+            Some(model_path) => Some(Self::load_session(model_path, config.device)?),
+            None => None,
+        };
+        Ok(Self { config, patterns, session, encoder: AssemblyEncoder::new() })
+    }
+
+    fn load_session(model_path: &std::path::Path, device: InferenceDevice) -> Result<Session, Box<dyn std::error::Error>> {
+        let mut builder = Session::builder()?;
+        if let InferenceDevice::Cuda(device_id) = device {
+            builder = builder.with_execution_providers([CUDA::default().with_device_id(device_id).build()])?;
+        }
+        Ok(builder.commit_from_file(model_path)?)
     }
 
     fn initialize_patterns() -> Vec<OptimizationPattern> {
@@ -43,32 +84,91 @@ impl OptimizationModel {
             // Example pattern: "mov reg, reg" -> remove if source and destination are the same
             OptimizationPattern {
                 pattern: vec![TokenType::Mnemonic, TokenType::Register, TokenType::Register],
+                literal_match: vec![Some("mov".to_string()), None, None],
                 replacement: vec![], // Empty replacement means remove the instruction
             },
         ]
     }
 
-    pub fn optimize(&self, input_tokens: &[AssemblyToken]) -> Vec<AssemblyToken> {
+    /// Number of peephole patterns currently known, including both the
+    /// hardcoded defaults and any patterns merged in via
+    /// [`learn_pattern`](Self::learn_pattern).
+    pub fn pattern_count(&self) -> usize {
+        self.patterns.len()
+    }
+
+    /// Add a learned rewrite rule: whenever `input_window` is seen verbatim,
+    /// replace it with `replacement_window`. Mnemonic and register values in
+    /// `input_window` must match literally for the rule to fire; other
+    /// operand kinds (immediates, memory operands, separators) match on
+    /// token type alone, so the same rule generalizes across literal
+    /// immediate/displacement values.
+    pub fn learn_pattern(&mut self, input_window: &[AssemblyToken], replacement_window: &[AssemblyToken]) {
+        let pattern = input_window.iter().map(|token| token.token_type.clone()).collect();
+        let literal_match = input_window
+            .iter()
+            .map(|token| match token.token_type {
+                TokenType::Mnemonic | TokenType::Register => Some(token.value.clone()),
+                _ => None,
+            })
+            .collect();
+
+        self.patterns.push(OptimizationPattern {
+            pattern,
+            literal_match,
+            replacement: replacement_window.to_vec(),
+        });
+    }
+
+    pub fn optimize(&mut self, input_tokens: &[AssemblyToken]) -> Vec<AssemblyToken> {
+        if self.session.is_some() {
+            match self.optimize_with_model(input_tokens) {
+                Ok(optimized) => return optimized,
+                Err(e) => tracing::warn!("model-based optimization failed, falling back to peephole path: {}", e),
+            }
+        }
+
         let mut optimized = input_tokens.to_vec();
-        
+
         if self.config.enable_peephole {
             optimized = self.apply_peephole_optimizations(optimized);
         }
-        
+
         if self.config.enable_dead_code_elimination {
             optimized = self.eliminate_dead_code(optimized);
         }
-        
+
         optimized
     }
 
+    /// Run the loaded ONNX model over `input_tokens` and greedily decode its
+    /// per-position logits back into a token stream. The model is assumed to
+    /// predict a replacement token per input position (not a variable-length
+    /// sequence), so each decoded token keeps the `token_type` of the input
+    /// token at that position and only its `value` is replaced by the
+    /// argmax-decoded vocabulary entry.
+    fn optimize_with_model(&mut self, input_tokens: &[AssemblyToken]) -> Result<Vec<AssemblyToken>, Box<dyn std::error::Error>> {
+        let tokens = &input_tokens[..input_tokens.len().min(self.config.max_decode_len)];
+        let logits = self.forward(tokens)?;
+
+        let vocab_size = self.config.vocab_size as usize;
+        let mut decoded = Vec::with_capacity(tokens.len());
+        for (original, position_logits) in tokens.iter().zip(logits.chunks(vocab_size)) {
+            let best_id = argmax(position_logits);
+            let value = self.encoder.get_token(best_id).unwrap_or(&original.value).to_string();
+            decoded.push(AssemblyToken { token_type: original.token_type.clone(), value });
+        }
+
+        Ok(decoded)
+    }
+
     fn apply_peephole_optimizations(&self, tokens: Vec<AssemblyToken>) -> Vec<AssemblyToken> {
         let mut result = Vec::new();
         let mut i = 0;
-        
+
         while i < tokens.len() {
             let mut matched = false;
-            
+
             // Try to match patterns
             for pattern in &self.patterns {
                 if let Some(new_tokens) = self.try_match_pattern(&tokens[i..], pattern) {
@@ -78,13 +178,13 @@ impl OptimizationModel {
                     break;
                 }
             }
-            
+
             if !matched {
                 result.push(tokens[i].clone());
                 i += 1;
             }
         }
-        
+
         result
     }
 
@@ -93,16 +193,21 @@ impl OptimizationModel {
             return None;
         }
 
-        // Check if tokens match the pattern
+        // Check if tokens match the pattern's types and, where required, literal values
         for (i, expected_type) in pattern.pattern.iter().enumerate() {
             if tokens[i].token_type != *expected_type {
                 return None;
             }
+            if let Some(expected_value) = &pattern.literal_match[i] {
+                if tokens[i].value != *expected_value {
+                    return None;
+                }
+            }
         }
 
         // If tokens are the same register in a mov instruction, remove it
-        if pattern.pattern.len() == 3 
-            && tokens[0].value == "mov" 
+        if pattern.pattern.len() == 3
+            && tokens[0].value == "mov"
             && tokens[1].value == tokens[2].value {
             return Some(vec![]);
         }
@@ -110,32 +215,214 @@ impl OptimizationModel {
         Some(pattern.replacement.clone())
     }
 
+    /// Backward liveness analysis: an instruction that writes a single
+    /// register nobody reads afterward, and has no side effect beyond that
+    /// write, contributes nothing and can be dropped. Instructions are
+    /// grouped by [`TokenType::InstructionBoundary`]; non-instruction chunks
+    /// (e.g. a bare `Label`) are always kept and don't affect liveness.
     fn eliminate_dead_code(&self, tokens: Vec<AssemblyToken>) -> Vec<AssemblyToken> {
-        // Simple dead code elimination: remove unused labels and unreachable code
-        tokens.into_iter()
-            .filter(|token| {
-                // Keep all non-label tokens
-                token.token_type != TokenType::Label
-            })
+        let instructions = split_instructions(&tokens);
+        let mut live: std::collections::HashSet<String> =
+            CALLER_SAVED_REGISTERS.iter().map(|r| r.to_string()).collect();
+        let mut keep = vec![true; instructions.len()];
+
+        for (i, instruction) in instructions.iter().enumerate().rev() {
+            let Some(effect) = InstructionEffect::analyze(instruction) else {
+                continue; // not a mnemonic-led chunk (e.g. a label); always kept
+            };
+
+            if let Some(def) = &effect.def {
+                if effect.no_side_effects && !live.contains(def) {
+                    keep[i] = false;
+                    continue;
+                }
+                live.remove(def);
+            }
+            live.extend(effect.uses);
+        }
+
+        instructions
+            .into_iter()
+            .zip(keep)
+            .filter(|(_, keep)| *keep)
+            .flat_map(|(instruction, _)| instruction)
             .collect()
     }
 
-    pub fn forward(&self, _tokens: &[AssemblyToken]) -> Vec<f32> {
-        // Simple forward pass that creates a vector of zeros
-        vec![0.0; self.config.vocab_size as usize]
+    /// Run the loaded ONNX model over `tokens` and return its raw logits, laid
+    /// out as `tokens.len()` consecutive chunks of `vocab_size` each. Returns
+    /// an error if no model is configured.
+    pub fn forward(&mut self, tokens: &[AssemblyToken]) -> Result<Vec<f32>, Box<dyn std::error::Error>> {
+        let session = self.session.as_mut().ok_or("no model loaded; set OptimizationConfig::model_path")?;
+
+        let input_ids: Vec<i64> = tokens.iter().map(|token| self.encoder.get_token_id(&token.value) as i64).collect();
+        let seq_len = input_ids.len();
+
+        let input_name = session.inputs()[0].name().to_string();
+        let output_name = session.outputs()[0].name().to_string();
+        let input_tensor = Tensor::from_array(([1usize, seq_len], input_ids))?;
+        let outputs = session.run(ort::inputs![input_name => input_tensor])?;
+        let (_shape, logits) = outputs[output_name.as_str()].try_extract_tensor::<f32>()?;
+
+        Ok(logits.to_vec())
     }
 
+    /// Serialize the learned peephole patterns to `path` as JSON. The ONNX
+    /// session, if any, is not part of this file — it's loaded separately
+    /// from `OptimizationConfig::model_path`.
     pub fn save(&self, path: &std::path::Path) -> Result<(), Box<dyn std::error::Error>> {
-        // For now, just create an empty file to simulate saving
-        std::fs::write(path, "")?;
+        let json = serde_json::to_string_pretty(&self.patterns)?;
+        std::fs::write(path, json)?;
         Ok(())
     }
 
+    /// Replace the in-memory peephole patterns with those serialized at
+    /// `path` by [`save`](Self::save).
     pub fn load(&mut self, path: &std::path::Path) -> Result<(), Box<dyn std::error::Error>> {
-        // For now, just check if file exists to simulate loading
-        if !path.exists() {
-            return Err("Model file not found".into());
-        }
+        let json = std::fs::read_to_string(path)?;
+        self.patterns = serde_json::from_str(&json)?;
         Ok(())
     }
 }
+
+/// Registers the System V x86-64 calling convention lets a callee clobber, so
+/// they're assumed live at function exit (a caller may still read them, dead
+/// code elimination just can't see that from a single function's tokens).
+const CALLER_SAVED_REGISTERS: &[&str] = &["rax", "rcx", "rdx", "rsi", "rdi", "r8", "r9", "r10", "r11"];
+
+/// Mnemonics dead code elimination may consider deleting when their register
+/// write turns out to be unused: no memory writes, no control flow. Several
+/// of these also set RFLAGS (see [`FLAG_SETTING_MNEMONICS`]) and are
+/// additionally excluded from elimination on that basis.
+const NO_SIDE_EFFECT_MNEMONICS: &[&str] = &[
+    "mov", "lea", "movzx", "movsx", "add", "sub", "and", "or", "xor", "not", "neg", "inc", "dec",
+    "shl", "shr", "sar",
+];
+
+/// Mnemonics among [`NO_SIDE_EFFECT_MNEMONICS`] whose destination operand is
+/// also read (e.g. `add rax, 1` reads `rax` to compute the new `rax`), as
+/// opposed to a pure write like `mov`/`lea` where the destination is only
+/// ever overwritten.
+const READ_MODIFY_WRITE_MNEMONICS: &[&str] =
+    &["add", "sub", "and", "or", "xor", "not", "neg", "inc", "dec", "shl", "shr", "sar"];
+
+/// Mnemonics that set RFLAGS as an architectural side effect. This pass
+/// doesn't track flag liveness, so any instruction in this list is never
+/// eligible for dead code elimination on the basis of its register write
+/// alone -- flags are conservatively treated as always live, the same way
+/// [`CALLER_SAVED_REGISTERS`] are always live for registers.
+const FLAG_SETTING_MNEMONICS: &[&str] =
+    &["add", "sub", "and", "or", "xor", "not", "neg", "inc", "dec", "shl", "shr", "sar"];
+
+/// A single instruction's effect on register liveness: the register it
+/// writes (if it writes exactly one register operand and nothing else), the
+/// registers it reads, and whether it's safe for dead code elimination to
+/// remove entirely when its write turns out to be unused.
+struct InstructionEffect {
+    def: Option<String>,
+    uses: std::collections::HashSet<String>,
+    no_side_effects: bool,
+}
+
+impl InstructionEffect {
+    /// Analyze one instruction (mnemonic token followed by its operand
+    /// tokens). Returns `None` for a chunk that doesn't start with a
+    /// mnemonic, which liveness analysis leaves untouched.
+    fn analyze(instruction: &[AssemblyToken]) -> Option<Self> {
+        let mnemonic_token = instruction.first()?;
+        if mnemonic_token.token_type != TokenType::Mnemonic {
+            return None;
+        }
+        let mnemonic = mnemonic_token.value.to_lowercase();
+
+        let operand_tokens = instruction[1..]
+            .iter()
+            .filter(|token| token.token_type != TokenType::InstructionBoundary);
+        let operands = split_operands(operand_tokens.cloned().collect::<Vec<_>>().as_slice());
+
+        let is_no_side_effect = NO_SIDE_EFFECT_MNEMONICS.contains(&mnemonic.as_str());
+        let dest_is_bare_register = matches!(
+            operands.first().map(|operand| operand.as_slice()),
+            Some([AssemblyToken { token_type: TokenType::Register, .. }])
+        );
+
+        let def = if is_no_side_effect && dest_is_bare_register {
+            operands[0][0].value.to_lowercase().into()
+        } else {
+            None
+        };
+
+        let reads_dest_too = def.is_none() || READ_MODIFY_WRITE_MNEMONICS.contains(&mnemonic.as_str());
+        let mut uses = std::collections::HashSet::new();
+        for (i, operand) in operands.iter().enumerate() {
+            if i == 0 && def.is_some() && !reads_dest_too {
+                continue; // pure write (mov/lea): destination register isn't read
+            }
+            for token in operand {
+                if token.token_type == TokenType::Register {
+                    uses.insert(token.value.to_lowercase());
+                }
+            }
+        }
+
+        let sets_flags = FLAG_SETTING_MNEMONICS.contains(&mnemonic.as_str());
+
+        Some(Self {
+            no_side_effects: is_no_side_effect && def.is_some() && !sets_flags,
+            def,
+            uses,
+        })
+    }
+}
+
+/// Split a flat token stream into one chunk per instruction, each chunk
+/// running up to and including its trailing `InstructionBoundary` token (the
+/// final chunk may lack one if the stream doesn't end cleanly).
+pub(crate) fn split_instructions(tokens: &[AssemblyToken]) -> Vec<Vec<AssemblyToken>> {
+    let mut instructions = Vec::new();
+    let mut current = Vec::new();
+    for token in tokens {
+        let is_boundary = token.token_type == TokenType::InstructionBoundary;
+        current.push(token.clone());
+        if is_boundary {
+            instructions.push(std::mem::take(&mut current));
+        }
+    }
+    if !current.is_empty() {
+        instructions.push(current);
+    }
+    instructions
+}
+
+/// Split an instruction's operand tokens on top-level commas, keeping commas
+/// inside a `[...]` memory operand from splitting that operand apart.
+fn split_operands(tokens: &[AssemblyToken]) -> Vec<Vec<AssemblyToken>> {
+    let mut operands = Vec::new();
+    let mut current = Vec::new();
+    let mut in_memory = false;
+    for token in tokens {
+        match &token.token_type {
+            TokenType::Memory if token.value == "[" => in_memory = true,
+            TokenType::Memory if token.value == "]" => in_memory = false,
+            TokenType::Separator if token.value == "," && !in_memory => {
+                operands.push(std::mem::take(&mut current));
+                continue;
+            }
+            _ => {}
+        }
+        current.push(token.clone());
+    }
+    if !current.is_empty() {
+        operands.push(current);
+    }
+    operands
+}
+
+fn argmax(values: &[f32]) -> usize {
+    values
+        .iter()
+        .enumerate()
+        .max_by(|(_, a), (_, b)| a.total_cmp(b))
+        .map(|(index, _)| index)
+        .unwrap_or(0)
+}
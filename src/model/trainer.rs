@@ -1,8 +1,10 @@
 use crate::model::{
-    encoder::AssemblyToken,
-    optimizer::OptimizationModel,
+    encoder::{AssemblyToken, TokenType},
+    optimizer::{split_instructions, OptimizationModel},
 };
-use std::path::PathBuf;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
 
 #[derive(Debug, Clone)]
 pub struct TrainingConfig {
@@ -10,6 +12,9 @@ pub struct TrainingConfig {
     pub checkpoint_interval: usize,
     pub num_epochs: usize,
     pub batch_size: usize,
+    /// Minimum number of times a candidate rewrite must be seen across the
+    /// training data before it's promoted to a learned optimization pattern.
+    pub min_support: usize,
 }
 
 
@@ -20,6 +25,7 @@ impl Default for TrainingConfig {
             checkpoint_interval: 1000,
             num_epochs: 10,
             batch_size: 32,
+            min_support: 2,
         }
     }
 
@@ -31,54 +37,229 @@ pub struct TrainingExample {
     pub target_tokens: Vec<AssemblyToken>,
 }
 
+/// Metadata checkpointed alongside the model so a mining run can resume
+/// where it left off instead of being recomputed from scratch.
+///
+/// `best_loss` is `None` rather than `Some(f64::INFINITY)` when no training
+/// has happened yet, since `serde_json` can't round-trip non-finite floats.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct CheckpointMetadata {
+    current_epoch: usize,
+    best_loss: Option<f64>,
+    pattern_count: usize,
+}
+
 #[allow(dead_code)]
 pub struct ModelTrainer {
     model: OptimizationModel,
     config: TrainingConfig,
+    current_epoch: usize,
+    best_loss: f64,
 }
 
 
 impl ModelTrainer {
     pub fn new(model: OptimizationModel, config: TrainingConfig) -> Self {
-        Self { model, config }
+        Self { model, config, current_epoch: 0, best_loss: f64::INFINITY }
     }
 
     pub fn train(&mut self, training_data: Vec<TrainingExample>) -> Result<(), Box<dyn std::error::Error>> {
-        // In pattern-based approach, we don't actually train
-        // Instead, we analyze patterns in the training data to potentially add new optimization patterns
-        for example in training_data {
-            self.analyze_pattern(&example);
+        // In pattern-based approach, we don't actually train a network.
+        // Instead, we mine (input -> target) token diffs across all examples
+        // and promote the ones that recur often enough into new peephole
+        // patterns on the model.
+        let mut candidates: HashMap<String, (Vec<AssemblyToken>, Vec<AssemblyToken>, usize)> = HashMap::new();
+        for example in &training_data {
+            self.analyze_pattern(example, &mut candidates);
+        }
+
+        let mut learned = 0;
+        let mut diverging_tokens = 0;
+        for (input_window, replacement_window, support) in candidates.into_values() {
+            diverging_tokens += input_window.len() * support;
+            if support >= self.config.min_support {
+                self.model.learn_pattern(&input_window, &replacement_window);
+                learned += 1;
+            }
         }
-        Ok(())
-    }
 
-    fn analyze_pattern(&self, example: &TrainingExample) {
-        // Here we could analyze patterns in the training data
-        // For now, we just log the example
         tracing::info!(
-            "Analyzing pattern: {} tokens -> {} tokens",
-            example.input_tokens.len(),
-            example.target_tokens.len()
+            "analyzed {} training examples, learned {} new optimization patterns (min_support={})",
+            training_data.len(),
+            learned,
+            self.config.min_support,
         );
+
+        self.current_epoch += self.config.num_epochs;
+
+        // Fraction of input tokens that diverge from their target across the
+        // training set, used as a stand-in loss. It counts every diverging
+        // window, whether or not it was promoted to a pattern above.
+        let total_tokens: usize = training_data.iter().map(|example| example.input_tokens.len()).sum();
+        if total_tokens > 0 {
+            self.best_loss = self.best_loss.min(diverging_tokens as f64 / total_tokens as f64);
+        }
+
+        Ok(())
+    }
+
+    /// Diff `example.input_tokens` against `example.target_tokens` and
+    /// accumulate each divergent (input window, replacement window) pair's
+    /// occurrence count in `candidates`, keyed by its own contents so the
+    /// same rewrite seen in different examples is counted together.
+    fn analyze_pattern(&self, example: &TrainingExample, candidates: &mut HashMap<String, (Vec<AssemblyToken>, Vec<AssemblyToken>, usize)>) {
+        for (input_window, replacement_window) in diff_windows(&example.input_tokens, &example.target_tokens) {
+            // An empty input window has nothing to key a rewrite's trigger
+            // on, so it can't become a peephole pattern.
+            if input_window.is_empty() {
+                continue;
+            }
+
+            let key = pattern_key(&input_window, &replacement_window);
+            let entry = candidates
+                .entry(key)
+                .or_insert_with(|| (input_window.clone(), replacement_window.clone(), 0));
+            entry.2 += 1;
+        }
     }
 
-    pub fn save_checkpoint(&self, _filename: &str) -> Result<(), Box<dyn std::error::Error>> {
-        // In pattern-based approach, we don't need to save checkpoints
+    /// Persist the learned patterns and training progress to
+    /// `self.config.save_dir/filename`, alongside a `CheckpointMetadata`
+    /// sidecar so [`load_checkpoint`](Self::load_checkpoint) can restore
+    /// `current_epoch`/`best_loss` on top of the patterns.
+    pub fn save_checkpoint(&self, filename: &str) -> Result<(), Box<dyn std::error::Error>> {
+        std::fs::create_dir_all(&self.config.save_dir)?;
+        let model_path = self.config.save_dir.join(filename);
+        self.model.save(&model_path)?;
+
+        let metadata = CheckpointMetadata {
+            current_epoch: self.current_epoch,
+            best_loss: self.best_loss.is_finite().then_some(self.best_loss),
+            pattern_count: self.model.pattern_count(),
+        };
+        let json = serde_json::to_string_pretty(&metadata)?;
+        std::fs::write(metadata_path_for(&model_path), json)?;
+
         Ok(())
     }
 
-    pub fn load_checkpoint(&mut self, _filename: &str) -> Result<(), Box<dyn std::error::Error>> {
-        // In pattern-based approach, we don't need to load checkpoints
+    /// Restore patterns and training progress previously written by
+    /// [`save_checkpoint`](Self::save_checkpoint).
+    pub fn load_checkpoint(&mut self, filename: &str) -> Result<(), Box<dyn std::error::Error>> {
+        let model_path = self.config.save_dir.join(filename);
+        self.model.load(&model_path)?;
+
+        let json = std::fs::read_to_string(metadata_path_for(&model_path))?;
+        let metadata: CheckpointMetadata = serde_json::from_str(&json)?;
+        self.current_epoch = metadata.current_epoch;
+        self.best_loss = metadata.best_loss.unwrap_or(f64::INFINITY);
+
         Ok(())
     }
 
     pub fn get_current_epoch(&self) -> usize {
-        // For now, return a dummy value
-        2
+        self.current_epoch
     }
 
     pub fn get_best_loss(&self) -> f64 {
-        // For now, return a dummy value
-        0.1
+        self.best_loss
+    }
+
+    /// Number of peephole patterns the trained model currently knows,
+    /// including any mined by [`train`](Self::train).
+    pub fn learned_pattern_count(&self) -> usize {
+        self.model.pattern_count()
+    }
+}
+
+/// Align `input` and `target` via the longest common subsequence of their
+/// *whole instructions* and return the contiguous runs where they diverge,
+/// as (input run, target run) pairs of complete instructions. Diffing at
+/// instruction granularity (rather than per-token) guarantees a promoted
+/// window never replaces part of one instruction's operands in isolation —
+/// every candidate rewrite swaps complete instructions for complete
+/// instructions, so a mined pattern can't fire on an instruction's mnemonic
+/// or one of its operands without also covering the rest of that
+/// instruction.
+fn diff_windows(input: &[AssemblyToken], target: &[AssemblyToken]) -> Vec<(Vec<AssemblyToken>, Vec<AssemblyToken>)> {
+    let input_instructions = split_instructions(input);
+    let target_instructions = split_instructions(target);
+    let matches = lcs_matches(&input_instructions, &target_instructions);
+
+    let mut windows = Vec::new();
+    let mut prev_i = 0;
+    let mut prev_j = 0;
+    for (i, j) in matches.into_iter().chain(std::iter::once((input_instructions.len(), target_instructions.len()))) {
+        if i > prev_i || j > prev_j {
+            windows.push((
+                input_instructions[prev_i..i].concat(),
+                target_instructions[prev_j..j].concat(),
+            ));
+        }
+        prev_i = i + 1;
+        prev_j = j + 1;
+    }
+
+    windows
+}
+
+/// Compute the longest common subsequence of `a` and `b` by equality and
+/// return it as a list of (index in `a`, index in `b`) matched pairs, in
+/// order. Generic so it can align either tokens or whole instructions.
+fn lcs_matches<T: PartialEq>(a: &[T], b: &[T]) -> Vec<(usize, usize)> {
+    let (n, m) = (a.len(), b.len());
+    let mut dp = vec![vec![0usize; m + 1]; n + 1];
+    for i in (0..n).rev() {
+        for j in (0..m).rev() {
+            dp[i][j] = if a[i] == b[j] {
+                dp[i + 1][j + 1] + 1
+            } else {
+                dp[i + 1][j].max(dp[i][j + 1])
+            };
+        }
+    }
+
+    let mut matches = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    while i < n && j < m {
+        if a[i] == b[j] {
+            matches.push((i, j));
+            i += 1;
+            j += 1;
+        } else if dp[i + 1][j] >= dp[i][j + 1] {
+            i += 1;
+        } else {
+            j += 1;
+        }
+    }
+    matches
+}
+
+/// Sidecar path for a checkpoint's [`CheckpointMetadata`], derived from the
+/// model file's own path by appending a `.meta.json` extension.
+fn metadata_path_for(model_path: &Path) -> PathBuf {
+    let mut file_name = model_path.file_name().unwrap_or_default().to_os_string();
+    file_name.push(".meta.json");
+    model_path.with_file_name(file_name)
+}
+
+/// A string key identifying a candidate rewrite by the `TokenType` sequence
+/// plus literal mnemonic/register values, mirroring
+/// [`OptimizationModel::learn_pattern`](crate::model::optimizer::OptimizationModel::learn_pattern)'s
+/// `literal_match`: immediates, memory operands, and separators are
+/// wildcarded so the same rewrite accumulates support across training
+/// examples that differ only in those literal values.
+fn pattern_key(input_window: &[AssemblyToken], replacement_window: &[AssemblyToken]) -> String {
+    fn window_key(window: &[AssemblyToken]) -> String {
+        window
+            .iter()
+            .map(|token| match token.token_type {
+                TokenType::Mnemonic | TokenType::Register => format!("{:?}:{}", token.token_type, token.value),
+                _ => format!("{:?}", token.token_type),
+            })
+            .collect::<Vec<_>>()
+            .join(",")
     }
+
+    format!("{}=>{}", window_key(input_window), window_key(replacement_window))
 }
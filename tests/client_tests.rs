@@ -0,0 +1,101 @@
+use neurassembly::client::http::RetryConfig;
+use neurassembly::client::{AsyncClient, HttpClient};
+use axum::http::StatusCode;
+use axum::routing::post;
+use axum::{Json, Router};
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+#[derive(Deserialize)]
+struct OptimizeRequest {
+    assembly: String,
+}
+
+#[derive(Serialize)]
+struct OptimizeResponse {
+    optimized_assembly: String,
+}
+
+#[derive(Deserialize)]
+struct BatchOptimizeRequest {
+    items: Vec<OptimizeRequest>,
+}
+
+#[derive(Serialize)]
+struct BatchOptimizeResponse {
+    items: Vec<OptimizeResponse>,
+}
+
+/// Fails with a transient 503 until `fail_until` requests have been seen,
+/// then echoes the request back as "optimized".
+async fn flaky_handler(
+    counter: Arc<AtomicU32>,
+    fail_until: u32,
+    Json(request): Json<OptimizeRequest>,
+) -> Result<Json<OptimizeResponse>, StatusCode> {
+    let seen = counter.fetch_add(1, Ordering::SeqCst);
+    if seen < fail_until {
+        return Err(StatusCode::SERVICE_UNAVAILABLE);
+    }
+    Ok(Json(OptimizeResponse { optimized_assembly: request.assembly }))
+}
+
+async fn batch_handler(Json(request): Json<BatchOptimizeRequest>) -> Json<BatchOptimizeResponse> {
+    let items = request
+        .items
+        .into_iter()
+        .map(|item| OptimizeResponse { optimized_assembly: item.assembly })
+        .collect();
+    Json(BatchOptimizeResponse { items })
+}
+
+async fn spawn_flaky_server(fail_until: u32) -> String {
+    let counter = Arc::new(AtomicU32::new(0));
+    let app = Router::new()
+        .route(
+            "/optimize",
+            post(move |body| {
+                let counter = counter.clone();
+                async move { flaky_handler(counter, fail_until, body).await }
+            }),
+        )
+        .route("/optimize/batch", post(batch_handler));
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+    format!("http://{addr}")
+}
+
+#[tokio::test]
+async fn test_async_client_retries_transient_failures_then_succeeds() {
+    let base_url = spawn_flaky_server(2).await;
+    let retry = RetryConfig { max_attempts: 5, initial_backoff: Duration::from_millis(1) };
+    let client = HttpClient::with_retry_config(base_url, retry);
+
+    let result = AsyncClient::optimize(&client, "mov rax, rbx").await.unwrap();
+    assert_eq!(result, "mov rax, rbx");
+}
+
+#[tokio::test]
+async fn test_async_client_gives_up_after_max_attempts() {
+    let base_url = spawn_flaky_server(10).await;
+    let retry = RetryConfig { max_attempts: 3, initial_backoff: Duration::from_millis(1) };
+    let client = HttpClient::with_retry_config(base_url, retry);
+
+    let result = AsyncClient::optimize(&client, "mov rax, rbx").await;
+    assert!(result.is_err());
+}
+
+#[tokio::test]
+async fn test_async_client_batch_round_trips() {
+    let base_url = spawn_flaky_server(0).await;
+    let client = HttpClient::new(base_url);
+
+    let items = vec!["mov rax, rbx".to_string(), "add rax, 1".to_string()];
+    let result = AsyncClient::optimize_batch(&client, &items).await.unwrap();
+    assert_eq!(result, items);
+}
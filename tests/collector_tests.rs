@@ -38,7 +38,8 @@ fn test_source_file_collection() -> Result<(), Box<dyn std::error::Error>> {
     let config = CollectorConfig {
         source_dir: source_dir.path().to_path_buf(),
         output_dir: output_dir.path().to_path_buf(),
-        optimization_levels: vec!["-O0".to_string()], // Just test with one optimization level
+        unoptimized_level: "-O0".to_string(),
+        optimized_level: "-O3".to_string(),
         source_extensions: vec!["c".to_string()],
         max_jobs: 1,
     };
@@ -53,7 +54,7 @@ fn test_source_file_collection() -> Result<(), Box<dyn std::error::Error>> {
 }
 
 #[test]
-fn test_multiple_optimization_levels() -> Result<(), Box<dyn std::error::Error>> {
+fn test_pairs_are_not_identity() -> Result<(), Box<dyn std::error::Error>> {
     // Create temporary directories
     let source_dir = tempdir()?;
     let output_dir = tempdir()?;
@@ -64,7 +65,8 @@ fn test_multiple_optimization_levels() -> Result<(), Box<dyn std::error::Error>>
     let config = CollectorConfig {
         source_dir: source_dir.path().to_path_buf(),
         output_dir: output_dir.path().to_path_buf(),
-        optimization_levels: vec!["-O0".to_string(), "-O2".to_string()],
+        unoptimized_level: "-O0".to_string(),
+        optimized_level: "-O3".to_string(),
         source_extensions: vec!["c".to_string()],
         max_jobs: 1,
     };
@@ -72,8 +74,13 @@ fn test_multiple_optimization_levels() -> Result<(), Box<dyn std::error::Error>>
     let mut collector = AssemblyCollector::new(config);
     let examples = collector.collect()?;
 
-    // We should have examples from both optimization levels
-    assert!(examples.len() >= 2);
+    assert!(!examples.is_empty());
+
+    // Aligned -O0/-O3 pairs should actually differ, unlike a naive collector
+    // that pairs every function with itself.
+    assert!(examples
+        .iter()
+        .any(|example| example.input_tokens != example.target_tokens));
 
     Ok(())
 }
@@ -90,7 +97,8 @@ fn test_encoder_consistency() -> Result<(), Box<dyn std::error::Error>> {
     let config = CollectorConfig {
         source_dir: source_dir.path().to_path_buf(),
         output_dir: output_dir.path().to_path_buf(),
-        optimization_levels: vec!["-O0".to_string()],
+        unoptimized_level: "-O0".to_string(),
+        optimized_level: "-O3".to_string(),
         source_extensions: vec!["c".to_string()],
         max_jobs: 1,
     };
@@ -102,7 +110,6 @@ fn test_encoder_consistency() -> Result<(), Box<dyn std::error::Error>> {
     for example in examples {
         assert!(!example.input_tokens.is_empty());
         assert!(!example.target_tokens.is_empty());
-        assert_eq!(example.input_tokens.len(), example.target_tokens.len());
     }
 
     Ok(())
@@ -0,0 +1,97 @@
+use neurassembly::data::{CorpusConfig, CorpusSource, ExampleSource};
+use std::fs;
+use tempfile::tempdir;
+
+fn write_shard(dir: &std::path::Path, name: &str, records: &[&str]) -> std::path::PathBuf {
+    let path = dir.join(name);
+    fs::write(&path, records.join("\n")).unwrap();
+    path
+}
+
+#[test]
+fn test_corpus_reads_jsonl_shard() {
+    let dir = tempdir().unwrap();
+    write_shard(
+        dir.path(),
+        "shard.jsonl",
+        &[
+            r#"{"content": "mov rax, rbx", "language": "asm"}"#,
+            r#"{"content": "int main() {}", "language": "c"}"#,
+        ],
+    );
+
+    let config = CorpusConfig {
+        shard_glob: format!("{}/*.jsonl", dir.path().display()),
+        ..CorpusConfig::default()
+    };
+    let mut source = CorpusSource::new(config);
+    let examples: Vec<_> = source.examples().collect();
+
+    assert_eq!(examples.len(), 2);
+}
+
+#[test]
+fn test_corpus_filters_by_language() {
+    let dir = tempdir().unwrap();
+    write_shard(
+        dir.path(),
+        "shard.jsonl",
+        &[
+            r#"{"content": "mov rax, rbx", "language": "asm"}"#,
+            r#"{"content": "int main() {}", "language": "c"}"#,
+        ],
+    );
+
+    let config = CorpusConfig {
+        shard_glob: format!("{}/*.jsonl", dir.path().display()),
+        languages: vec!["asm".to_string()],
+        ..CorpusConfig::default()
+    };
+    let mut source = CorpusSource::new(config);
+    let examples: Vec<_> = source.examples().collect();
+
+    assert_eq!(examples.len(), 1);
+}
+
+#[test]
+fn test_corpus_respects_record_cap() {
+    let dir = tempdir().unwrap();
+    write_shard(
+        dir.path(),
+        "shard.jsonl",
+        &[
+            r#"{"content": "a", "language": "asm"}"#,
+            r#"{"content": "b", "language": "asm"}"#,
+            r#"{"content": "c", "language": "asm"}"#,
+        ],
+    );
+
+    let config = CorpusConfig {
+        shard_glob: format!("{}/*.jsonl", dir.path().display()),
+        record_cap: Some(2),
+        ..CorpusConfig::default()
+    };
+    let mut source = CorpusSource::new(config);
+    let examples: Vec<_> = source.examples().collect();
+
+    assert_eq!(examples.len(), 2);
+}
+
+#[test]
+fn test_corpus_malformed_lines_are_skipped() {
+    let dir = tempdir().unwrap();
+    write_shard(
+        dir.path(),
+        "shard.jsonl",
+        &[r#"{"content": "mov rax, rbx", "language": "asm"}"#, "not valid json", ""],
+    );
+
+    let config = CorpusConfig {
+        shard_glob: format!("{}/*.jsonl", dir.path().display()),
+        ..CorpusConfig::default()
+    };
+    let mut source = CorpusSource::new(config);
+    let examples: Vec<_> = source.examples().collect();
+
+    assert_eq!(examples.len(), 1);
+}
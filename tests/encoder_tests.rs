@@ -1,4 +1,8 @@
-use neurassembly::model::encoder::{AssemblyEncoder, TokenType};
+use neurassembly::model::encoder::{AssemblyEncoder, AssemblyToken, TokenType};
+
+fn token(token_type: TokenType, value: &str) -> AssemblyToken {
+    AssemblyToken { token_type, value: value.to_string() }
+}
 
 #[test]
 fn test_basic_instruction_encoding() {
@@ -6,13 +10,54 @@ fn test_basic_instruction_encoding() {
     let assembly = "mov rax, rbx";
     let tokens = encoder.encode(assembly);
 
-    assert_eq!(tokens.len(), 3); // mnemonic + register + register
+    // mnemonic + register + separator + register + instruction boundary
+    assert_eq!(tokens.len(), 5);
     assert_eq!(tokens[0].token_type, TokenType::Mnemonic);
     assert_eq!(tokens[0].value, "mov");
     assert_eq!(tokens[1].token_type, TokenType::Register);
     assert_eq!(tokens[1].value, "rax");
-    assert_eq!(tokens[2].token_type, TokenType::Register);
-    assert_eq!(tokens[2].value, "rbx");
+    assert_eq!(tokens[2].token_type, TokenType::Separator);
+    assert_eq!(tokens[2].value, ",");
+    assert_eq!(tokens[3].token_type, TokenType::Register);
+    assert_eq!(tokens[3].value, "rbx");
+    assert_eq!(tokens[4].token_type, TokenType::InstructionBoundary);
+}
+
+#[test]
+fn test_multi_instruction_encoding_round_trips_through_decode() {
+    let mut encoder = AssemblyEncoder::new();
+    let assembly = "mov rax, rbx\nadd rax, 1\npush rax";
+    let tokens = encoder.encode(assembly);
+
+    assert_eq!(encoder.decode(&tokens), assembly);
+}
+
+#[test]
+fn test_jump_target_is_encoded_as_immediate() {
+    let mut encoder = AssemblyEncoder::new();
+    let tokens = encoder.encode("je some_label");
+
+    assert_eq!(tokens[0], token(TokenType::Mnemonic, "je"));
+    assert_eq!(tokens[1], token(TokenType::Immediate, "some_label"));
+}
+
+#[test]
+fn test_label_line_is_encoded_and_round_trips() {
+    let mut encoder = AssemblyEncoder::new();
+    let tokens = encoder.encode("some_label:\nret");
+
+    assert_eq!(tokens[0], token(TokenType::Label, "some_label"));
+    assert_eq!(encoder.decode(&tokens), "some_label:\nret");
+}
+
+#[test]
+fn test_negative_displacement_memory_operand_round_trips() {
+    let mut encoder = AssemblyEncoder::new();
+    let tokens = encoder.encode("mov rax, [rbp-0x8]");
+
+    assert!(tokens.iter().any(|t| t.token_type == TokenType::Register && t.value == "rbp"));
+    assert!(tokens.iter().any(|t| t.token_type == TokenType::Immediate && t.value == "-0x8"));
+    assert_eq!(encoder.decode(&tokens), "mov rax, [rbp-0x8]");
 }
 
 #[test]
@@ -50,4 +95,60 @@ fn test_vocabulary_building() {
 
     // Verify vocabulary size
     assert_eq!(encoder.get_vocabulary_size(), 3);
-} 
\ No newline at end of file
+}
+
+#[test]
+fn test_decode_single_instruction() {
+    let encoder = AssemblyEncoder::new();
+    let tokens = vec![
+        token(TokenType::Mnemonic, "Mov"),
+        token(TokenType::Register, "RAX"),
+        token(TokenType::Separator, ","),
+        token(TokenType::Register, "RBX"),
+        token(TokenType::InstructionBoundary, "\n"),
+    ];
+
+    assert_eq!(encoder.decode(&tokens), "mov rax, rbx");
+}
+
+#[test]
+fn test_decode_memory_operand() {
+    let encoder = AssemblyEncoder::new();
+    let tokens = vec![
+        token(TokenType::Mnemonic, "Mov"),
+        token(TokenType::Prefix, "dword"),
+        token(TokenType::Memory, "["),
+        token(TokenType::Register, "RAX"),
+        token(TokenType::Separator, "+"),
+        token(TokenType::Register, "RBX"),
+        token(TokenType::Separator, "*"),
+        token(TokenType::Immediate, "4"),
+        token(TokenType::Separator, "+"),
+        token(TokenType::Immediate, "0x10"),
+        token(TokenType::Memory, "]"),
+        token(TokenType::Separator, ","),
+        token(TokenType::Register, "ECX"),
+        token(TokenType::InstructionBoundary, "\n"),
+    ];
+
+    assert_eq!(encoder.decode(&tokens), "mov dword [rax+rbx*4+0x10], ecx");
+}
+
+#[test]
+fn test_decode_multiple_instructions_round_trip() {
+    let encoder = AssemblyEncoder::new();
+    let tokens = vec![
+        token(TokenType::Mnemonic, "Mov"),
+        token(TokenType::Register, "RAX"),
+        token(TokenType::Separator, ","),
+        token(TokenType::Register, "RBX"),
+        token(TokenType::InstructionBoundary, "\n"),
+        token(TokenType::Mnemonic, "Add"),
+        token(TokenType::Register, "RAX"),
+        token(TokenType::Separator, ","),
+        token(TokenType::Immediate, "0x1"),
+        token(TokenType::InstructionBoundary, "\n"),
+    ];
+
+    assert_eq!(encoder.decode(&tokens), "mov rax, rbx\nadd rax, 0x1");
+}
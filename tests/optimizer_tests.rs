@@ -1,33 +1,40 @@
 use neurassembly::model::{
-    encoder::{AssemblyEncoder, AssemblyToken},
+    encoder::{AssemblyEncoder, AssemblyToken, TokenType},
     optimizer::{OptimizationModel, OptimizationConfig},
 };
 
+fn token(token_type: TokenType, value: &str) -> AssemblyToken {
+    AssemblyToken { token_type, value: value.to_string() }
+}
+
+fn boundary() -> AssemblyToken {
+    token(TokenType::InstructionBoundary, "\n")
+}
+
 #[test]
-fn test_model_creation() {
+fn test_model_creation() -> Result<(), Box<dyn std::error::Error>> {
     let config = OptimizationConfig::default();
-    let model = OptimizationModel::new(config);
-    // Just testing that model creation doesn't panic
+    let _model = OptimizationModel::new(config)?;
+    Ok(())
 }
 
 #[test]
-fn test_model_forward_pass() {
+fn test_forward_without_model_errors() -> Result<(), Box<dyn std::error::Error>> {
     let mut encoder = AssemblyEncoder::new();
-    let assembly = "mov rax, rbx";
-    let tokens = encoder.encode(assembly);
+    let tokens = encoder.encode("mov rax, rbx");
 
     let config = OptimizationConfig {
         vocab_size: encoder.get_vocabulary_size() as i64,
         ..Default::default()
     };
-    let model = OptimizationModel::new(config);
+    let mut model = OptimizationModel::new(config)?;
 
-    let output = model.forward(&tokens);
-    assert_eq!(output.size(), &[1, tokens.len() as i64, config.vocab_size]);
+    assert!(model.forward(&tokens).is_err());
+    Ok(())
 }
 
 #[test]
-fn test_model_optimization() {
+fn test_model_optimization_falls_back_to_peephole_without_model() -> Result<(), Box<dyn std::error::Error>> {
     let mut encoder = AssemblyEncoder::new();
     let assembly = "mov rax, rbx\nadd rax, 1";
     let tokens = encoder.encode(assembly);
@@ -36,31 +43,124 @@ fn test_model_optimization() {
         vocab_size: encoder.get_vocabulary_size() as i64,
         ..Default::default()
     };
-    let model = OptimizationModel::new(config);
+    let mut model = OptimizationModel::new(config)?;
 
     let optimized_tokens = model.optimize(&tokens);
     assert!(!optimized_tokens.is_empty());
+    Ok(())
+}
+
+#[test]
+fn test_dead_write_to_non_caller_saved_register_is_eliminated() -> Result<(), Box<dyn std::error::Error>> {
+    let config = OptimizationConfig { enable_peephole: false, ..Default::default() };
+    let mut model = OptimizationModel::new(config)?;
+
+    // "mov rbx, rax" writes rbx, which nothing reads afterward and which
+    // isn't caller-saved, so it should be dropped entirely.
+    let tokens = vec![
+        token(TokenType::Mnemonic, "mov"),
+        token(TokenType::Register, "rbx"),
+        token(TokenType::Separator, ","),
+        token(TokenType::Register, "rax"),
+        boundary(),
+    ];
+
+    let optimized = model.optimize(&tokens);
+    assert!(optimized.iter().all(|t| t.value != "rbx"));
+    Ok(())
+}
+
+#[test]
+fn test_memory_store_survives_dead_code_elimination() -> Result<(), Box<dyn std::error::Error>> {
+    let config = OptimizationConfig { enable_peephole: false, ..Default::default() };
+    let mut model = OptimizationModel::new(config)?;
+
+    // "mov [rax], rbx" is a store; it must never be eliminated even though
+    // nothing downstream reads memory.
+    let tokens = vec![
+        token(TokenType::Mnemonic, "mov"),
+        token(TokenType::Memory, "["),
+        token(TokenType::Register, "rax"),
+        token(TokenType::Memory, "]"),
+        token(TokenType::Separator, ","),
+        token(TokenType::Register, "rbx"),
+        boundary(),
+    ];
+
+    let optimized = model.optimize(&tokens);
+    assert!(optimized.iter().any(|t| t.token_type == TokenType::Mnemonic));
+    Ok(())
+}
+
+#[test]
+fn test_write_consumed_by_later_instruction_is_kept() -> Result<(), Box<dyn std::error::Error>> {
+    let config = OptimizationConfig { enable_peephole: false, ..Default::default() };
+    let mut model = OptimizationModel::new(config)?;
+
+    // "add rbx, 1" followed by "mov rax, rbx" reads rbx, so the add must
+    // survive even though rbx itself isn't caller-saved.
+    let tokens = vec![
+        token(TokenType::Mnemonic, "add"),
+        token(TokenType::Register, "rbx"),
+        token(TokenType::Separator, ","),
+        token(TokenType::Immediate, "0x1"),
+        boundary(),
+        token(TokenType::Mnemonic, "mov"),
+        token(TokenType::Register, "rax"),
+        token(TokenType::Separator, ","),
+        token(TokenType::Register, "rbx"),
+        boundary(),
+    ];
+
+    let optimized = model.optimize(&tokens);
+    let mnemonic_count = optimized.iter().filter(|t| t.token_type == TokenType::Mnemonic).count();
+    assert_eq!(mnemonic_count, 2);
+    Ok(())
+}
+
+#[test]
+fn test_flag_setting_instruction_before_branch_is_kept() -> Result<(), Box<dyn std::error::Error>> {
+    let config = OptimizationConfig { enable_peephole: false, ..Default::default() };
+    let mut model = OptimizationModel::new(config)?;
+
+    // "sub rbx, rax" writes rbx, which is dead and not caller-saved, but it
+    // also sets the flags that "je" reads -- it must survive even though its
+    // register write alone would otherwise be eligible for elimination.
+    let tokens = vec![
+        token(TokenType::Mnemonic, "sub"),
+        token(TokenType::Register, "rbx"),
+        token(TokenType::Separator, ","),
+        token(TokenType::Register, "rax"),
+        boundary(),
+        token(TokenType::Mnemonic, "je"),
+        token(TokenType::Immediate, "some_label"),
+        boundary(),
+    ];
+
+    let optimized = model.optimize(&tokens);
+    let mnemonic_count = optimized.iter().filter(|t| t.token_type == TokenType::Mnemonic).count();
+    assert_eq!(mnemonic_count, 2);
+    Ok(())
 }
 
 #[test]
 fn test_model_save_load() -> Result<(), Box<dyn std::error::Error>> {
-    use std::fs;
     use tempfile::tempdir;
 
     let config = OptimizationConfig::default();
-    let model = OptimizationModel::new(config.clone());
+    let model = OptimizationModel::new(config.clone())?;
 
     // Create a temporary directory for the model
     let dir = tempdir()?;
-    let model_path = dir.path().join("model.pt");
+    let model_path = dir.path().join("model.onnx");
 
     // Save the model
     model.save(&model_path)?;
     assert!(model_path.exists());
 
     // Load the model
-    let mut loaded_model = OptimizationModel::new(config);
+    let mut loaded_model = OptimizationModel::new(config)?;
     loaded_model.load(&model_path)?;
 
     Ok(())
-} 
\ No newline at end of file
+}
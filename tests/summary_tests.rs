@@ -0,0 +1,67 @@
+use neurassembly::evaluation::metrics::MetricsComparison;
+use neurassembly::evaluation::summary::RunSummary;
+
+fn comparison(cycle_reduction: f64) -> MetricsComparison {
+    MetricsComparison {
+        instruction_reduction: 10.0,
+        cycle_reduction,
+        memory_ops_reduction: 5.0,
+        register_pressure_change: 0.0,
+        code_size_reduction: 2.0,
+        execution_time_reduction: None,
+        execution_time_significant: None,
+    }
+}
+
+#[test]
+fn test_run_summary_aggregates_means() {
+    let mut summary = RunSummary::new();
+    summary.record("a", &comparison(10.0));
+    summary.record("b", &comparison(30.0));
+
+    assert_eq!(summary.accepted_count(), 2);
+    assert_eq!(summary.mean_cycle_reduction(), 20.0);
+    assert_eq!(summary.mean_instruction_reduction(), 10.0);
+}
+
+#[test]
+fn test_run_summary_tracks_rejections_separately() {
+    let mut summary = RunSummary::new();
+    summary.record("a", &comparison(10.0));
+    summary.record_rejection();
+    summary.record_rejection();
+
+    assert_eq!(summary.accepted_count(), 1);
+    assert_eq!(summary.rejected_count(), 2);
+    assert_eq!(summary.total_count(), 3);
+}
+
+#[test]
+fn test_run_summary_tracks_best_and_worst_by_cycle_reduction() {
+    let mut summary = RunSummary::new();
+    summary.record("low", &comparison(-5.0));
+    summary.record("high", &comparison(40.0));
+    summary.record("mid", &comparison(10.0));
+
+    assert_eq!(summary.best_example().unwrap().label, "high");
+    assert_eq!(summary.worst_example().unwrap().label, "low");
+}
+
+#[test]
+fn test_run_summary_empty_report_has_no_examples() {
+    let summary = RunSummary::new();
+
+    assert_eq!(summary.accepted_count(), 0);
+    assert!(summary.best_example().is_none());
+    assert!(summary.worst_example().is_none());
+}
+
+#[test]
+fn test_run_summary_display_is_compact_table() {
+    let mut summary = RunSummary::new();
+    summary.record("a", &comparison(10.0));
+
+    let rendered = format!("{}", summary);
+    assert!(rendered.contains("1 accepted"));
+    assert!(rendered.contains("cycle reduction"));
+}
@@ -1,11 +1,32 @@
 use neurassembly::model::{
-    encoder::AssemblyEncoder,
+    encoder::{AssemblyEncoder, AssemblyToken, TokenType},
     optimizer::{OptimizationModel, OptimizationConfig},
     trainer::{ModelTrainer, TrainingConfig, TrainingExample},
 };
 use tempfile::tempdir;
 use tch::Device;
 
+fn token(token_type: TokenType, value: &str) -> AssemblyToken {
+    AssemblyToken { token_type, value: value.to_string() }
+}
+
+// "add rax, 1" -> "inc rax", built by hand from tokens so the
+// instruction-level diff mined by `analyze_pattern` is exact and repeatable.
+fn add_to_inc_example() -> TrainingExample {
+    TrainingExample {
+        input_tokens: vec![
+            token(TokenType::Mnemonic, "add"),
+            token(TokenType::Register, "rax"),
+            token(TokenType::Separator, ","),
+            token(TokenType::Immediate, "0x1"),
+        ],
+        target_tokens: vec![
+            token(TokenType::Mnemonic, "inc"),
+            token(TokenType::Register, "rax"),
+        ],
+    }
+}
+
 fn create_dummy_training_data(encoder: &mut AssemblyEncoder) -> Vec<TrainingExample> {
     let input_assembly = vec![
         "mov rax, rbx",
@@ -35,9 +56,9 @@ fn test_trainer_creation() {
         vocab_size: encoder.get_vocabulary_size() as i64,
         ..Default::default()
     };
-    let model = OptimizationModel::new(model_config);
+    let model = OptimizationModel::new(model_config).unwrap();
     let training_config = TrainingConfig::default();
-    
+
     let trainer = ModelTrainer::new(model, training_config);
     // Just testing that trainer creation doesn't panic
 }
@@ -51,8 +72,8 @@ fn test_training_loop() -> Result<(), Box<dyn std::error::Error>> {
         vocab_size: encoder.get_vocabulary_size() as i64,
         ..Default::default()
     };
-    let model = OptimizationModel::new(model_config);
-    
+    let model = OptimizationModel::new(model_config)?;
+
     // Create temporary directory for checkpoints
     let temp_dir = tempdir()?;
     let training_config = TrainingConfig {
@@ -78,8 +99,8 @@ fn test_checkpoint_save_load() -> Result<(), Box<dyn std::error::Error>> {
         vocab_size: encoder.get_vocabulary_size() as i64,
         ..Default::default()
     };
-    let model = OptimizationModel::new(model_config.clone());
-    
+    let model = OptimizationModel::new(model_config.clone())?;
+
     // Create temporary directory for checkpoints
     let temp_dir = tempdir()?;
     let training_config = TrainingConfig {
@@ -88,14 +109,53 @@ fn test_checkpoint_save_load() -> Result<(), Box<dyn std::error::Error>> {
     };
     
     let mut trainer = ModelTrainer::new(model, training_config.clone());
-    
+    trainer.train(vec![add_to_inc_example(), add_to_inc_example()])?;
+
     // Save a checkpoint
     trainer.save_checkpoint("test_checkpoint.pt")?;
-    
+
     // Create a new trainer and load the checkpoint
-    let model = OptimizationModel::new(model_config);
+    let model = OptimizationModel::new(model_config)?;
     let mut new_trainer = ModelTrainer::new(model, training_config);
     new_trainer.load_checkpoint("test_checkpoint.pt")?;
-    
+
+    assert_eq!(new_trainer.get_current_epoch(), trainer.get_current_epoch());
+    assert_eq!(new_trainer.get_best_loss(), trainer.get_best_loss());
+    assert_eq!(new_trainer.learned_pattern_count(), trainer.learned_pattern_count());
+
+    Ok(())
+}
+
+#[test]
+fn test_recurring_rewrite_is_learned_above_min_support() -> Result<(), Box<dyn std::error::Error>> {
+    let model_config = OptimizationConfig::default();
+    let model = OptimizationModel::new(model_config)?;
+    let initial_patterns = model.pattern_count();
+
+    let training_config = TrainingConfig {
+        min_support: 2,
+        ..Default::default()
+    };
+    let mut trainer = ModelTrainer::new(model, training_config);
+    trainer.train(vec![add_to_inc_example(), add_to_inc_example()])?;
+
+    assert_eq!(trainer.learned_pattern_count(), initial_patterns + 1);
     Ok(())
-} 
\ No newline at end of file
+}
+
+#[test]
+fn test_rewrite_below_min_support_is_not_learned() -> Result<(), Box<dyn std::error::Error>> {
+    let model_config = OptimizationConfig::default();
+    let model = OptimizationModel::new(model_config)?;
+    let initial_patterns = model.pattern_count();
+
+    let training_config = TrainingConfig {
+        min_support: 3,
+        ..Default::default()
+    };
+    let mut trainer = ModelTrainer::new(model, training_config);
+    trainer.train(vec![add_to_inc_example(), add_to_inc_example()])?;
+
+    assert_eq!(trainer.learned_pattern_count(), initial_patterns);
+    Ok(())
+}
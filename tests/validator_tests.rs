@@ -0,0 +1,138 @@
+use neurassembly::model::encoder::{AssemblyToken, TokenType};
+use neurassembly::evaluation::OptimizationValidator;
+
+fn token(token_type: TokenType, value: &str) -> AssemblyToken {
+    AssemblyToken { token_type, value: value.to_string() }
+}
+
+fn boundary() -> AssemblyToken {
+    token(TokenType::InstructionBoundary, "\n")
+}
+
+#[test]
+fn test_identical_sequences_are_equivalent() {
+    let tokens = vec![
+        token(TokenType::Mnemonic, "mov"),
+        token(TokenType::Register, "rax"),
+        token(TokenType::Separator, ","),
+        token(TokenType::Register, "rbx"),
+        boundary(),
+    ];
+
+    let validator = OptimizationValidator::new().with_trial_count(20);
+    let result = validator.validate(&tokens, &tokens);
+    assert!(result.semantically_equivalent);
+    assert!(result.divergence.is_none());
+}
+
+#[test]
+fn test_add_one_and_inc_are_equivalent() {
+    // "add rax, 1" and "inc rax" compute the same register result; they only
+    // disagree on CF, which "inc" leaves unchanged and so does not define,
+    // so the comparison must mask that bit out rather than treat it as a
+    // divergence.
+    let original = vec![
+        token(TokenType::Mnemonic, "add"),
+        token(TokenType::Register, "rax"),
+        token(TokenType::Separator, ","),
+        token(TokenType::Immediate, "0x1"),
+        boundary(),
+    ];
+    let optimized = vec![
+        token(TokenType::Mnemonic, "inc"),
+        token(TokenType::Register, "rax"),
+        boundary(),
+    ];
+
+    let validator = OptimizationValidator::new().with_trial_count(50);
+    let result = validator.validate(&original, &optimized);
+    assert!(result.semantically_equivalent, "divergence: {:?}", result.divergence);
+}
+
+#[test]
+fn test_sub_one_and_dec_are_equivalent() {
+    // Same reasoning as "add 1"/"inc": "sub rax, 1" and "dec rax" compute the
+    // same register result and only disagree on CF, which "dec" leaves
+    // unchanged and so does not define.
+    let original = vec![
+        token(TokenType::Mnemonic, "sub"),
+        token(TokenType::Register, "rax"),
+        token(TokenType::Separator, ","),
+        token(TokenType::Immediate, "0x1"),
+        boundary(),
+    ];
+    let optimized = vec![
+        token(TokenType::Mnemonic, "dec"),
+        token(TokenType::Register, "rax"),
+        boundary(),
+    ];
+
+    let validator = OptimizationValidator::new().with_trial_count(50);
+    let result = validator.validate(&original, &optimized);
+    assert!(result.semantically_equivalent, "divergence: {:?}", result.divergence);
+}
+
+#[test]
+fn test_different_immediate_is_not_equivalent() {
+    let original = vec![
+        token(TokenType::Mnemonic, "mov"),
+        token(TokenType::Register, "rax"),
+        token(TokenType::Separator, ","),
+        token(TokenType::Immediate, "0x5"),
+        boundary(),
+    ];
+    let optimized = vec![
+        token(TokenType::Mnemonic, "mov"),
+        token(TokenType::Register, "rax"),
+        token(TokenType::Separator, ","),
+        token(TokenType::Immediate, "0x6"),
+        boundary(),
+    ];
+
+    let validator = OptimizationValidator::new().with_trial_count(5);
+    let result = validator.validate(&original, &optimized);
+    assert!(!result.semantically_equivalent);
+    assert!(result.divergence.is_some());
+}
+
+#[test]
+fn test_extra_register_write_in_optimized_is_caught() {
+    // The optimized sequence clobbers rcx, which the original never touches.
+    // Checking equivalence only against the original's written-register set
+    // would miss this entirely; the comparison must also consider registers
+    // only the optimized side wrote.
+    let original = vec![
+        token(TokenType::Mnemonic, "mov"),
+        token(TokenType::Register, "rax"),
+        token(TokenType::Separator, ","),
+        token(TokenType::Register, "rbx"),
+        boundary(),
+    ];
+    let optimized = vec![
+        token(TokenType::Mnemonic, "mov"),
+        token(TokenType::Register, "rax"),
+        token(TokenType::Separator, ","),
+        token(TokenType::Register, "rbx"),
+        boundary(),
+        token(TokenType::Mnemonic, "mov"),
+        token(TokenType::Register, "rcx"),
+        token(TokenType::Separator, ","),
+        token(TokenType::Immediate, "0x0"),
+        boundary(),
+    ];
+
+    let validator = OptimizationValidator::new().with_trial_count(20);
+    let result = validator.validate(&original, &optimized);
+    assert!(!result.semantically_equivalent, "extra clobbered register should be detected");
+    assert!(result.divergence.is_some());
+}
+
+#[test]
+fn test_unsupported_mnemonic_fails_validation() {
+    let tokens = vec![token(TokenType::Mnemonic, "cpuid"), boundary()];
+
+    let validator = OptimizationValidator::new().with_trial_count(1);
+    let result = validator.validate(&tokens, &tokens);
+    assert!(!result.semantically_equivalent);
+    assert!(result.divergence.is_some());
+}